@@ -0,0 +1,30 @@
+use std::env;
+use std::process::Command;
+
+/// Runs the given command and returns its trimmed stdout, if it succeeded.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8(output.stdout).ok()?;
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn main() {
+    let git_commit = command_output("git", &["rev-parse", "--short", "HEAD"])
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version =
+        command_output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_string());
+
+    let build_date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+    println!("cargo:rustc-env=DDAI_GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=DDAI_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=DDAI_BUILD_DATE={}", build_date);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
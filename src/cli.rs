@@ -1,21 +1,85 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 use crate::commands::business;
+use crate::commands::config::{self, ConfigSource};
 use crate::commands::project;
+use crate::commands::version;
 
 #[derive(Parser)]
 #[command(
     name = "ddai",
-    version = "0.1.0",
+    version,
     about = "A command-line tool to integrate DDD and AI principles"
 )]
 pub struct Cli {
+    /// The output format used to report command results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// The format of the logs written to stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "DDAI_LOG_FORMAT",
+        default_value_t = LogFormat::Pretty
+    )]
+    pub log_format: LogFormat,
+
+    /// A `.env` style file whose variables are loaded before running the command, e.g.
+    /// holding `OPENAI_API_KEY`. Variables which are already set keep their value
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Let the `--env-file` variables replace those which are already set
+    #[arg(long, global = true, requires = "env_file")]
+    pub override_env: bool,
+
+    /// Where the log format was taken from, filled in from the parsed arg matches
+    #[arg(skip = ConfigSource::Default)]
+    pub log_format_source: ConfigSource,
+
     #[command(subcommand)]
     pub commands: Commands,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable messages
+    Text,
+
+    /// Machine-readable JSON documents printed to stdout
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable log lines
+    Pretty,
+
+    /// One JSON object per log event, for log aggregation
+    Json,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     Project(project::ProjectArgs),
     Business(business::BusinessArgs),
+    Config(config::ConfigArgs),
+
+    /// Print the version of ddai
+    Version(version::VersionArgs),
+}
+
+impl Cli {
+    /// Whether the invoked command asked to suppress everything but its result and errors.
+    pub fn quiet(&self) -> bool {
+        match &self.commands {
+            Commands::Business(args) => args.quiet(),
+            Commands::Project(args) => args.quiet(),
+            Commands::Config(_) | Commands::Version(_) => false,
+        }
+    }
 }
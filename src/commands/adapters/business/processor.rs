@@ -1,84 +1,598 @@
-use std::fs::{create_dir_all, File};
+use std::borrow::Cow;
+use std::fs::{copy, create_dir_all, read_dir, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
-use crate::core::business::types::{BusinessError, Definition, Processor};
-use crate::core::registry::types::FileVersion;
-use crate::core::types::PathBufWrapper;
+use tracing::warn;
+
+use crate::core::business::types::{
+    BusinessError, Definition, DefinitionFile, Processor, BUSINESS_FILE_EXTENSION,
+};
+use crate::core::document::types::{DocumentError, FilePath, FsProcessor};
+use crate::core::registry::types::{FileLayout, FileLocation, FileVersion};
+use crate::core::types::{PathBufWrapper, Validator};
 
 #[derive(Debug, Clone)]
-pub(crate) struct ProcessorAdapter<T: PathBufWrapper> {
+pub(crate) struct ProcessorAdapter<T: PathBufWrapper, D: FsProcessor> {
     pathbuf: T,
+    document: D,
+    lossy: bool,
 }
 
-impl<T> ProcessorAdapter<T>
+impl<T, D> ProcessorAdapter<T, D>
 where
     T: PathBufWrapper,
+    D: FsProcessor,
 {
-    pub fn new(pathbuf: T) -> Self {
-        ProcessorAdapter { pathbuf }
+    pub fn new(pathbuf: T, document: D) -> Self {
+        ProcessorAdapter {
+            pathbuf,
+            document,
+            lossy: false,
+        }
     }
+
+    /// `with_lossy` makes reads replace invalid UTF-8 sequences instead of failing.
+    pub fn with_lossy(mut self, lossy: bool) -> Self {
+        self.lossy = lossy;
+        self
+    }
+
+    fn file_path(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: &FileLocation,
+    ) -> PathBuf {
+        let dir = match &location.dir {
+            Some(dir) => dir.clone(),
+            None => self.pathbuf.to_path_buf(),
+        };
+
+        dir.join(relative_path(definition, version, location.layout))
+    }
+}
+
+/// Parses a `{version}.md` file name, anything else is not a definition version.
+fn parse_version(file_name: &str) -> Option<FileVersion> {
+    let version = FileVersion::from(file_name.strip_suffix(BUSINESS_FILE_EXTENSION)?);
+    version.validate().ok().map(|_| version)
 }
 
-impl<T> Processor for ProcessorAdapter<T>
+/// Path of a definition version relative to the businesses directory.
+fn relative_path(definition: &Definition, version: &FileVersion, layout: FileLayout) -> PathBuf {
+    match layout {
+        FileLayout::Nested => {
+            PathBuf::from(definition.stem()).join(format!("{}{}", version, BUSINESS_FILE_EXTENSION))
+        }
+        FileLayout::Flat => PathBuf::from(format!(
+            "{}-{}{}",
+            definition.stem(),
+            version,
+            BUSINESS_FILE_EXTENSION
+        )),
+    }
+}
+
+impl<T, D> Processor for ProcessorAdapter<T, D>
 where
     T: PathBufWrapper,
+    D: FsProcessor,
 {
-    fn define(&self, definition: Definition, version: FileVersion) -> Result<(), BusinessError> {
-        // first check if the directory exists, if not create it
-        // the directory is based on the "Definition" name
-        let dir_path = self.pathbuf.to_path_buf().join(definition.as_str());
-        if !dir_path.exists() {
-            create_dir_all(&dir_path).map_err(|err| BusinessError::FsError(err))?;
+    fn define(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+        content: &str,
+    ) -> Result<PathBuf, BusinessError> {
+        // first check if the parent directory exists, if not create it
+        // for the nested layout it is based on the "Definition" name, without any file extension
+        // an existing file is never overwritten, see `App::define` for accepting duplicates
+        let file_path = self.file_path(&definition, &version, &location);
+        if file_path.exists() {
+            return Err(BusinessError::AlreadyExists(format!(
+                "{} {}",
+                definition.stem(),
+                version
+            )));
         }
 
-        // create a file with the name format is "{version}.md"
-        // we only need to create the file, not write to it, it's like using "touch" command
-        let file_name = format!("{}.md", version.to_string());
-        let file_path = dir_path.join(file_name);
-        _ = File::create(&file_path).map_err(|err| BusinessError::FsError(err))?;
+        if let Some(dir_path) = file_path.parent() {
+            if !dir_path.exists() {
+                create_dir_all(dir_path).map_err(BusinessError::FsError)?;
+            }
+        }
 
+        // create the file, either "{name}/{version}.md" or "{name}-{version}.md"
+        // without any content it's like using "touch" command
+        let mut file = File::create(&file_path).map_err(BusinessError::FsError)?;
+        file.write_all(content.as_bytes())
+            .map_err(BusinessError::FsError)?;
+
+        Ok(file_path)
+    }
+
+    fn exists(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: &FileLocation,
+    ) -> bool {
+        self.file_path(definition, version, location).is_file()
+    }
+
+    fn path(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: &FileLocation,
+    ) -> PathBuf {
+        self.file_path(definition, version, location)
+    }
+
+    fn export(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+        target_dir: PathBuf,
+    ) -> Result<(), BusinessError> {
+        let file_path = self.file_path(&definition, &version, &location);
+        if !file_path.is_file() {
+            return Err(DocumentError::NotFound(file_path.display().to_string()).into());
+        }
+
+        let target_path = target_dir.join(relative_path(&definition, &version, location.layout));
+        if let Some(dir_path) = target_path.parent() {
+            create_dir_all(dir_path).map_err(BusinessError::FsError)?;
+        }
+
+        copy(&file_path, &target_path).map_err(BusinessError::FsError)?;
         Ok(())
     }
+
+    fn scan(&self) -> Result<Vec<DefinitionFile>, BusinessError> {
+        let dir = self.pathbuf.to_path_buf();
+        if !dir.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut found = vec![];
+        for entry in read_dir(&dir).map_err(BusinessError::FsError)? {
+            let path = entry.map_err(BusinessError::FsError)?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            if path.is_dir() {
+                for inner in read_dir(&path).map_err(BusinessError::FsError)? {
+                    let inner = inner.map_err(BusinessError::FsError)?.path();
+                    let version = inner
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(parse_version);
+
+                    if let Some(version) = version.filter(|_| inner.is_file()) {
+                        found.push(DefinitionFile {
+                            definition: Definition::from(name),
+                            version,
+                            layout: FileLayout::Nested,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            // flat files are named `{name}-{version}.md`, the name may contain dashes itself
+            let flat = name
+                .strip_suffix(BUSINESS_FILE_EXTENSION)
+                .and_then(|name| name.rsplit_once('-'))
+                .and_then(|(stem, version)| {
+                    parse_version(&format!("{}{}", version, BUSINESS_FILE_EXTENSION))
+                        .map(|version| (stem, version))
+                });
+
+            if let Some((stem, version)) = flat.filter(|(stem, _)| !stem.is_empty()) {
+                found.push(DefinitionFile {
+                    definition: Definition::from(stem),
+                    version,
+                    layout: FileLayout::Flat,
+                });
+            }
+        }
+
+        found.sort_by(|a, b| {
+            (a.definition.as_str(), &a.version).cmp(&(b.definition.as_str(), &b.version))
+        });
+        Ok(found)
+    }
+
+    fn read(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+    ) -> Result<String, BusinessError> {
+        let file_path = self.file_path(&definition, &version, &location);
+        let mut reader = self.document.read(FilePath::from(file_path.clone()))?;
+
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|err| DocumentError::ReadError(err.to_string()))?;
+
+        let (content, substituted) = decode(&file_path, bytes, self.lossy)?;
+        if substituted {
+            warn!(
+                "Invalid UTF-8 in {} was replaced with replacement characters",
+                file_path.display()
+            );
+        }
+
+        Ok(content)
+    }
+}
+
+/// Decodes a definition file, strictly unless `lossy` is set.
+///
+/// Along with the content, it tells whether replacement characters were substituted.
+fn decode(path: &Path, bytes: Vec<u8>, lossy: bool) -> Result<(String, bool), DocumentError> {
+    if !lossy {
+        let content = String::from_utf8(bytes).map_err(|err| {
+            DocumentError::ReadError(format!(
+                "{} is not valid UTF-8, use --lossy to read it anyway: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        return Ok((content, false));
+    }
+
+    match String::from_utf8_lossy(&bytes) {
+        Cow::Borrowed(content) => Ok((content.to_string(), false)),
+        Cow::Owned(content) => Ok((content, true)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mockall::mock;
-    use std::path::PathBuf;
 
-    mock!(
-        FakePathBufWrapper {}
+    use crate::commands::adapters::document::processor::ProcessorAdapter as DocumentProcessorAdapter;
 
-        impl PathBufWrapper for FakePathBufWrapper {
-            fn to_path_buf(&self) -> PathBuf;
-            fn dir_name(&self) -> Option<String>;
-            fn exists(&self) -> bool;
-        }
-    );
+    fn adapter(dir: &Path) -> ProcessorAdapter<PathBuf, DocumentProcessorAdapter> {
+        ProcessorAdapter::new(dir.to_path_buf(), DocumentProcessorAdapter::new())
+    }
 
     #[test]
     fn test_define_creates_directory_and_file() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let temp_dir_pathbuf = temp_dir.path().to_path_buf();
-        let temp_dir_pathbuf_cloned = temp_dir_pathbuf.clone();
-
-        let mut pathbuf = MockFakePathBufWrapper::new();
-        pathbuf
-            .expect_to_path_buf()
-            .returning(move || temp_dir_pathbuf.clone());
-        pathbuf.expect_exists().returning(|| true);
-
-        let processor = ProcessorAdapter::new(pathbuf);
+        let processor = adapter(temp_dir.path());
         let definition = Definition::from("test_business");
         let version = FileVersion::new();
-        let result = processor.define(definition.clone(), version.clone());
-        assert!(result.is_ok());
+        let path = processor
+            .define(
+                definition.clone(),
+                version.clone(),
+                FileLayout::Nested.into(),
+                "",
+            )
+            .unwrap();
 
-        let dir_path = temp_dir_pathbuf_cloned.join(definition.as_str());
+        let dir_path = temp_dir.path().join(definition.as_str());
         assert!(dir_path.exists(), "Directory should be created");
 
-        let file_name = format!("{}.md", version.to_string());
+        let file_name = format!("{}{}", version, BUSINESS_FILE_EXTENSION);
         let file_path = dir_path.join(file_name);
         assert!(file_path.exists(), "File should be created");
+        assert_eq!(path, file_path);
+    }
+
+    #[test]
+    fn test_define_strips_file_extension() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let processor = adapter(temp_dir.path());
+        let version = FileVersion::new();
+        assert!(processor
+            .define(
+                Definition::from("foo.md"),
+                version.clone(),
+                FileLayout::Nested.into(),
+                ""
+            )
+            .is_ok());
+        assert!(matches!(
+            processor.define(
+                Definition::from("foo"),
+                version,
+                FileLayout::Nested.into(),
+                ""
+            ),
+            Err(BusinessError::AlreadyExists(_))
+        ));
+
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("foo")]);
+
+        let file_path = temp_dir.path().join("foo").join("0.1.0.md");
+        assert!(file_path.exists(), "File should be created");
+        assert!(!temp_dir.path().join("foo.md").exists());
+    }
+
+    #[test]
+    fn test_define_existing_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("order").join("1.0.0.md");
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(&file_path, "# Order").unwrap();
+
+        let processor = adapter(temp_dir.path());
+        let version = FileVersion::from("1.0.0");
+        let result = processor.define(
+            Definition::from("order"),
+            version.clone(),
+            FileLayout::Nested.into(),
+            "",
+        );
+        match result {
+            Err(BusinessError::AlreadyExists(msg)) => assert_eq!(msg, "order 1.0.0"),
+            _ => panic!("Expected AlreadyExists"),
+        }
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Order");
+    }
+
+    #[test]
+    fn test_define_in_output_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let businesses_dir = temp_dir.path().join("businesses");
+        let output_dir = temp_dir.path().join("src");
+
+        let processor = adapter(&businesses_dir);
+        let definition = Definition::from("order");
+        let version = FileVersion::from("1.0.0");
+        let location = FileLocation::from(FileLayout::Nested).with_dir(Some(output_dir.clone()));
+
+        processor
+            .define(definition.clone(), version.clone(), location.clone(), "")
+            .unwrap();
+        assert!(output_dir.join("order").join("1.0.0.md").is_file());
+        assert!(!businesses_dir.join("order").exists());
+
+        assert!(processor.exists(&definition, &version, &location));
+        assert!(!processor.exists(&definition, &version, &FileLayout::Nested.into()));
+        assert_eq!(processor.read(definition, version, location).unwrap(), "");
+    }
+
+    #[test]
+    fn test_define_writes_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let processor = adapter(temp_dir.path());
+        let version = FileVersion::from("1.0.0");
+        processor
+            .define(
+                Definition::from("order"),
+                version.clone(),
+                FileLayout::Nested.into(),
+                "# Order\n",
+            )
+            .unwrap();
+
+        let content = processor.read(
+            Definition::from("order"),
+            version,
+            FileLayout::Nested.into(),
+        );
+        assert_eq!(content.unwrap(), "# Order\n");
+    }
+
+    #[test]
+    fn test_path_matches_defined_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let processor = adapter(temp_dir.path());
+        let definition = Definition::from("order");
+        let version = FileVersion::from("1.0.0");
+        for layout in [FileLayout::Nested, FileLayout::Flat] {
+            let location = FileLocation::from(layout);
+            assert!(!processor.path(&definition, &version, &location).exists());
+
+            let path = processor
+                .define(definition.clone(), version.clone(), location.clone(), "")
+                .unwrap();
+            assert_eq!(path, processor.path(&definition, &version, &location));
+            assert!(path.is_file());
+            assert!(path.starts_with(temp_dir.path()));
+        }
+        assert_eq!(
+            processor.path(&definition, &version, &FileLayout::Flat.into()),
+            temp_dir.path().join("order-1.0.0.md")
+        );
+    }
+
+    #[test]
+    fn test_exists() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("order")).unwrap();
+        std::fs::write(temp_dir.path().join("order").join("1.0.0.md"), "# Order").unwrap();
+        std::fs::write(temp_dir.path().join("billing-1.0.0.md"), "# Billing").unwrap();
+
+        let processor = adapter(temp_dir.path());
+        let version = FileVersion::from("1.0.0");
+        let order = Definition::from("order");
+        let billing = Definition::from("billing");
+
+        assert!(processor.exists(&order, &version, &FileLayout::Nested.into()));
+        assert!(!processor.exists(&order, &version, &FileLayout::Flat.into()));
+        assert!(!processor.exists(
+            &order,
+            &FileVersion::from("1.1.0"),
+            &FileLayout::Nested.into()
+        ));
+        assert!(processor.exists(&billing, &version, &FileLayout::Flat.into()));
+        assert!(!processor.exists(&billing, &version, &FileLayout::Nested.into()));
+    }
+
+    #[test]
+    fn test_export_keeps_relative_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("order-1.0.0.md");
+        std::fs::write(&source, "# Order").unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let processor = adapter(temp_dir.path());
+        let result = processor.export(
+            Definition::from("order"),
+            FileVersion::from("1.0.0"),
+            FileLayout::Flat.into(),
+            target_dir.path().to_path_buf(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            std::fs::read_to_string(target_dir.path().join("order-1.0.0.md")).unwrap(),
+            "# Order"
+        );
+
+        let missing = processor.export(
+            Definition::from("order"),
+            FileVersion::from("2.0.0"),
+            FileLayout::Nested.into(),
+            target_dir.path().to_path_buf(),
+        );
+        assert!(matches!(
+            missing,
+            Err(BusinessError::DocumentError(DocumentError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_scan_finds_both_layouts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("order")).unwrap();
+        for file in [
+            "order/1.0.0.md",
+            "order/notes.txt",
+            "order/draft.md",
+            "order-service-2.0.0.md",
+            "registry.json",
+            "README.md",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let processor = adapter(temp_dir.path());
+        let found = processor.scan().unwrap();
+        assert_eq!(
+            found,
+            vec![
+                DefinitionFile {
+                    definition: Definition::from("order"),
+                    version: FileVersion::from("1.0.0"),
+                    layout: FileLayout::Nested,
+                },
+                DefinitionFile {
+                    definition: Definition::from("order-service"),
+                    version: FileVersion::from("2.0.0"),
+                    layout: FileLayout::Flat,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_define_flat_layout() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let processor = adapter(temp_dir.path());
+        let version = FileVersion::from("1.0.0");
+        assert!(processor
+            .define(
+                Definition::from("order.md"),
+                version.clone(),
+                FileLayout::Flat.into(),
+                ""
+            )
+            .is_ok());
+
+        let file_path = temp_dir.path().join("order-1.0.0.md");
+        assert!(file_path.exists(), "File should be created");
+        assert!(!temp_dir.path().join("order").exists());
+
+        std::fs::write(&file_path, "## Bounded Contexts\n").unwrap();
+        let content = processor.read(Definition::from("order"), version, FileLayout::Flat.into());
+        assert_eq!(content.unwrap(), "## Bounded Contexts\n");
+    }
+
+    #[test]
+    fn test_read_returns_definition_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let dir_path = temp_dir.path().join("order");
+        create_dir_all(&dir_path).unwrap();
+        std::fs::write(dir_path.join("1.0.0.md"), "## Ubiquitous Language\n").unwrap();
+
+        let processor = adapter(temp_dir.path());
+        let content = processor.read(
+            Definition::from("order.md"),
+            FileVersion::from("1.0.0"),
+            FileLayout::Nested.into(),
+        );
+        assert_eq!(content.unwrap(), "## Ubiquitous Language\n");
+
+        let missing = processor.read(
+            Definition::from("order"),
+            FileVersion::from("2.0.0"),
+            FileLayout::Nested.into(),
+        );
+        assert!(matches!(
+            missing,
+            Err(BusinessError::DocumentError(DocumentError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_read_invalid_utf8() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let dir_path = temp_dir.path().join("order");
+        create_dir_all(&dir_path).unwrap();
+        std::fs::write(dir_path.join("0.1.0.md"), b"Order \xff total").unwrap();
+
+        let processor = adapter(temp_dir.path());
+        let strict = processor.read(
+            Definition::from("order"),
+            FileVersion::new(),
+            FileLayout::Nested.into(),
+        );
+        assert!(matches!(
+            strict,
+            Err(BusinessError::DocumentError(DocumentError::ReadError(_)))
+        ));
+
+        let processor = processor.with_lossy(true);
+        let lossy = processor.read(
+            Definition::from("order"),
+            FileVersion::new(),
+            FileLayout::Nested.into(),
+        );
+        assert_eq!(lossy.unwrap(), "Order \u{FFFD} total");
+    }
+
+    #[test]
+    fn test_decode_reports_substitution() {
+        let path = Path::new("order/0.1.0.md");
+
+        let (content, substituted) = decode(path, b"Order".to_vec(), true).unwrap();
+        assert_eq!(content, "Order");
+        assert!(!substituted);
+
+        let (content, substituted) = decode(path, b"Order \xff".to_vec(), true).unwrap();
+        assert_eq!(content, "Order \u{FFFD}");
+        assert!(substituted, "a warning should be emitted");
+
+        assert!(decode(path, b"Order \xff".to_vec(), false).is_err());
     }
 }
@@ -0,0 +1,136 @@
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind, Write};
+
+use crate::core::document::types::{DocumentError, FilePath, FileReader, FileWriter, FsProcessor};
+
+#[derive(Debug, Clone)]
+pub(crate) struct ProcessorAdapter {}
+
+impl ProcessorAdapter {
+    pub fn new() -> Self {
+        ProcessorAdapter {}
+    }
+}
+
+impl FsProcessor for ProcessorAdapter {
+    fn read(&self, path: FilePath) -> Result<FileReader, DocumentError> {
+        let path_buf = path.to_path_buf();
+        let file = File::open(&path_buf).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => DocumentError::NotFound(path_buf.display().to_string()),
+            _ => DocumentError::ReadError(err.to_string()),
+        })?;
+
+        Ok(Box::new(BufReader::new(file)))
+    }
+
+    fn write(&self, _path: FilePath, mut content: FileWriter) -> Result<(), DocumentError> {
+        // the writer is already bound to its target file, writing the document
+        // means flushing everything still buffered into it
+        content
+            .flush()
+            .map_err(|err| DocumentError::WriteError(err.to_string()))
+    }
+
+    fn write_all(&self, path: FilePath, content: &[u8]) -> Result<(), DocumentError> {
+        fs::write(path.to_path_buf(), content)
+            .map_err(|err| DocumentError::WriteError(err.to_string()))
+    }
+
+    fn exists(&self, path: FilePath) -> bool {
+        path.to_path_buf().exists()
+    }
+
+    fn create_dir(&self, path: FilePath) -> Result<(), DocumentError> {
+        fs::create_dir(path.to_path_buf()).map_err(|err| DocumentError::WriteError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufWriter, Read};
+
+    #[test]
+    fn test_read_existing_document() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("1.0.0.md");
+        std::fs::write(&file_path, "# Order").unwrap();
+
+        let processor = ProcessorAdapter::new();
+        let mut reader = processor
+            .read(FilePath::from(file_path.to_str().unwrap()))
+            .unwrap();
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "# Order");
+    }
+
+    #[test]
+    fn test_read_to_string_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("1.0.0.md");
+        std::fs::write(&file_path, "# Order\n\nÜbersicht\n").unwrap();
+
+        let processor = ProcessorAdapter::new();
+        let content = processor
+            .read_to_string(FilePath::from(file_path.clone()))
+            .unwrap();
+        assert_eq!(content, "# Order\n\nÜbersicht\n");
+
+        std::fs::write(&file_path, [0x23, 0xff, 0xfe]).unwrap();
+        let invalid = processor.read_to_string(FilePath::from(file_path));
+        assert!(matches!(invalid, Err(DocumentError::ReadError(_))));
+    }
+
+    #[test]
+    fn test_read_missing_document() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("missing.md");
+
+        let processor = ProcessorAdapter::new();
+        let result = processor.read(FilePath::from(file_path.to_str().unwrap()));
+        assert!(matches!(result, Err(DocumentError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_write_all_and_create_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().join("order");
+        let file_path = dir_path.join("1.0.0.md");
+
+        let processor = ProcessorAdapter::new();
+        assert!(!processor.exists(FilePath::from(dir_path.clone())));
+        assert!(processor
+            .write_all(FilePath::from(file_path.clone()), b"# Order")
+            .is_err());
+
+        processor
+            .create_dir(FilePath::from(dir_path.clone()))
+            .unwrap();
+        assert!(processor.exists(FilePath::from(dir_path.clone())));
+        assert!(processor.create_dir(FilePath::from(dir_path)).is_err());
+
+        processor
+            .write_all(FilePath::from(file_path.clone()), b"# Order")
+            .unwrap();
+        processor
+            .write_all(FilePath::from(file_path.clone()), b"# Order v2")
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Order v2");
+    }
+
+    #[test]
+    fn test_write_flushes_document() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("1.0.0.md");
+
+        let mut writer = BufWriter::new(File::create(&file_path).unwrap());
+        writer.write_all(b"# Order").unwrap();
+
+        let processor = ProcessorAdapter::new();
+        let result = processor.write(FilePath::from(file_path.to_str().unwrap()), writer);
+        assert!(result.is_ok());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Order");
+    }
+}
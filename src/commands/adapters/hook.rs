@@ -0,0 +1,85 @@
+use std::process::Command;
+
+use tracing::{info, warn};
+
+use crate::core::business::types::BusinessError;
+use crate::core::registry::types::FileVersion;
+
+/// `HookAdapter` runs a user provided shell command after a registry change.
+///
+/// The changed definition and version are passed to the command through the
+/// `DDAI_DEFINITION` and `DDAI_VERSION` environment variables. Its output is logged.
+#[derive(Debug, Clone)]
+pub(crate) struct HookAdapter {
+    command: String,
+}
+
+impl HookAdapter {
+    pub fn new(command: String) -> Self {
+        HookAdapter { command }
+    }
+
+    pub(crate) fn run(&self, definition: &str, version: &FileVersion) -> Result<(), BusinessError> {
+        info!("Running on-change hook: {}", self.command);
+        let output = shell(&self.command)
+            .env("DDAI_DEFINITION", definition)
+            .env("DDAI_VERSION", version.as_str())
+            .output()
+            .map_err(|err| BusinessError::HookFailed(format!("`{}`: {}", self.command, err)))?;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            info!("on-change hook: {}", line);
+        }
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            warn!("on-change hook: {}", line);
+        }
+
+        if !output.status.success() {
+            return Err(BusinessError::HookFailed(format!(
+                "`{}` {}",
+                self.command, output.status
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_receives_change() {
+        let hook = HookAdapter::new(
+            r#"test "$DDAI_DEFINITION" = order && test "$DDAI_VERSION" = 1.2.0"#.to_string(),
+        );
+        assert!(hook.run("order", &FileVersion::from("1.2.0")).is_ok());
+    }
+
+    #[test]
+    fn test_failing_hook() {
+        let hook = HookAdapter::new("echo rebuilding; exit 3".to_string());
+        match hook.run("order", &FileVersion::from("1.0.0")) {
+            Err(BusinessError::HookFailed(msg)) => {
+                assert!(msg.contains("exit 3"));
+                assert!(msg.contains("exit status: 3"));
+            }
+            _ => panic!("Expected HookFailed"),
+        }
+    }
+}
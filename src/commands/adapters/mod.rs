@@ -1,3 +1,5 @@
 pub(crate) mod business;
+pub(crate) mod document;
+pub(crate) mod hook;
 pub(crate) mod path_buf_wrapper;
 pub(crate) mod registry;
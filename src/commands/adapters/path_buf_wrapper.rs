@@ -19,19 +19,11 @@ impl PathBufWrapper for PathBufAdapter {
     }
 
     fn dir_name(&self) -> Option<String> {
-        self.pathbuf
-            .file_name()
-            .and_then(|name| name.to_str().map(String::from))
-            .or_else(|| {
-                self.pathbuf
-                    .parent()
-                    .and_then(|parent| parent.file_name())
-                    .and_then(|name| name.to_str().map(String::from))
-            })
+        self.pathbuf.dir_name()
     }
 
     fn exists(&self) -> bool {
-        self.pathbuf.exists()
+        PathBufWrapper::exists(&self.pathbuf)
     }
 }
 
@@ -54,5 +46,36 @@ mod tests {
                 .map(String::from)
         );
         assert!(adapter.exists());
+        assert!(adapter.is_dir());
+        assert_eq!(adapter.join("registry.json"), path.join("registry.json"));
+        assert!(!PathBufAdapter::new(adapter.join("registry.json")).is_dir());
+    }
+
+    #[test]
+    fn test_path_buf_adapter_dir_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let businesses_dir = temp_dir.path().join("businesses");
+
+        let label = |path: PathBuf| PathBufAdapter::new(path).dir_name();
+        assert_eq!(
+            label(businesses_dir.clone()),
+            Some("businesses".to_string())
+        );
+        assert_eq!(
+            label(PathBuf::from(format!("{}/", businesses_dir.display()))),
+            Some("businesses".to_string())
+        );
+        assert_eq!(
+            label(businesses_dir.join(".")),
+            Some("businesses".to_string())
+        );
+        assert_eq!(
+            label(businesses_dir.join("..")),
+            temp_dir.path().to_path_buf().dir_name()
+        );
+        assert_eq!(
+            label(PathBuf::from("/businesses")),
+            Some("businesses".to_string())
+        );
     }
 }
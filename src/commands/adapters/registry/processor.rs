@@ -1,34 +1,211 @@
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::fs::{read_to_string, rename, File};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
 
-use crate::core::registry::types::{Processor, Registry, RegistryError};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::core::registry::types::{
+    Processor, Registry, RegistryError, RegistryFormat, RegistryInfo, REGISTRY_SCHEMA_VERSION,
+};
+
+/// `RegistryFile` is the registry as written on disk, along with its schema version and
+/// the checksum of its content.
+///
+/// Both are absent from registries written before they were introduced, such registries
+/// are accepted as is and get them on their next write.
+#[derive(Serialize, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    schema_version: u32,
+
+    #[serde(flatten)]
+    registry: Registry,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+}
 
 #[derive(Debug, Clone)]
-pub(crate) struct ProcessorAdapter {}
+pub(crate) struct ProcessorAdapter {
+    lenient: bool,
+    strict: bool,
+}
 
 impl ProcessorAdapter {
     pub fn new() -> Self {
-        ProcessorAdapter {}
+        ProcessorAdapter {
+            lenient: false,
+            strict: false,
+        }
+    }
+
+    /// `with_lenient` makes JSON parsing tolerate trailing commas left by manual edits,
+    /// the registry is still written as strict JSON.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// `with_strict` fails parsing a registry whose checksum doesn't match its content,
+    /// otherwise the mismatch is only a warning.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    fn verify(&self, file_path: &Path, file: RegistryFile) -> Result<Registry, RegistryError> {
+        let Some(checksum) = file.checksum else {
+            debug!("{} has no checksum to verify", file_path.display());
+            return Ok(file.registry);
+        };
+
+        if file.registry.checksum()? == checksum {
+            return Ok(file.registry);
+        }
+
+        if self.strict {
+            return Err(RegistryError::ChecksumMismatch(
+                file_path.display().to_string(),
+            ));
+        }
+
+        warn!(
+            "{} does not match its checksum, it was modified outside of ddai",
+            file_path.display()
+        );
+        Ok(file.registry)
+    }
+}
+
+/// Removes the commas directly followed by a closing bracket or brace, outside of strings.
+fn strip_trailing_commas(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in json.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = json[index + 1..].trim_start().chars().next();
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+
+        out.push(c);
     }
+
+    out
+}
+
+/// The format follows the registry file extension, anything unknown is read as JSON.
+fn format_of(file_path: &Path) -> RegistryFormat {
+    RegistryFormat::from_path(file_path).unwrap_or_default()
 }
 
 impl Processor for ProcessorAdapter {
+    /// Writes the registry atomically: the content goes to a temporary sibling file first,
+    /// which then replaces the registry file, so an interrupted write never leaves a
+    /// truncated registry behind.
     fn build(&self, file_path: PathBuf, registry: Registry) -> Result<(), RegistryError> {
-        let file = File::create(file_path).map_err(|e| RegistryError::FsError(e))?;
+        let format = format_of(&file_path);
+        let temp_file_path = file_path.with_extension(format!("{}.tmp", format.extension()));
+        let registry = RegistryFile {
+            schema_version: REGISTRY_SCHEMA_VERSION,
+            checksum: Some(registry.checksum()?),
+            registry,
+        };
+        let file = File::create(&temp_file_path).map_err(RegistryError::FsError)?;
+
+        let mut writer = BufWriter::new(file);
+        match format {
+            RegistryFormat::Json => serde_json::to_writer(&mut writer, &registry)
+                .map_err(|e| RegistryError::FsError(e.into()))?,
+            RegistryFormat::Yaml => serde_yaml_ng::to_writer(&mut writer, &registry)
+                .map_err(|e| RegistryError::FsError(Error::new(ErrorKind::InvalidData, e)))?,
+        }
 
-        let writer = BufWriter::new(file);
-        serde_json::to_writer(writer, &registry).map_err(|e| RegistryError::FsError(e.into()))?;
+        let file = writer
+            .into_inner()
+            .map_err(|e| RegistryError::FsError(e.into_error()))?;
+        file.sync_all().map_err(RegistryError::FsError)?;
 
+        rename(&temp_file_path, &file_path).map_err(RegistryError::FsError)?;
         Ok(())
     }
 
     fn parse(&self, file_path: PathBuf) -> Result<Registry, RegistryError> {
-        let file = File::open(file_path).map_err(|e| RegistryError::FsError(e))?;
-        let reader = BufReader::new(file);
-        let registry: Registry =
-            serde_json::from_reader(reader).map_err(|e| RegistryError::FsError(e.into()))?;
-        Ok(registry)
+        let format = format_of(&file_path);
+        let file = File::open(&file_path).map_err(RegistryError::FsError)?;
+        let mut reader = BufReader::new(file);
+        let registry: RegistryFile = match format {
+            RegistryFormat::Json if self.lenient => {
+                let mut content = String::new();
+                reader
+                    .read_to_string(&mut content)
+                    .map_err(RegistryError::FsError)?;
+                serde_json::from_str(&strip_trailing_commas(&content))
+                    .map_err(|e| RegistryError::FsError(e.into()))?
+            }
+            RegistryFormat::Json => {
+                serde_json::from_reader(reader).map_err(|e| RegistryError::FsError(e.into()))?
+            }
+            RegistryFormat::Yaml => serde_yaml_ng::from_reader(reader)
+                .map_err(|e| RegistryError::FsError(Error::new(ErrorKind::InvalidData, e)))?,
+        };
+        self.verify(&file_path, registry)
+    }
+
+    /// Reads the schema version and file count straight from the file content, so they
+    /// are reported as written even for a registry the current layout would migrate.
+    fn inspect(&self, file_path: PathBuf) -> Result<RegistryInfo, RegistryError> {
+        let content = read_to_string(&file_path).map_err(RegistryError::FsError)?;
+        let value: serde_json::Value = match format_of(&file_path) {
+            RegistryFormat::Json if self.lenient => {
+                serde_json::from_str(&strip_trailing_commas(&content))
+                    .map_err(|e| RegistryError::FsError(e.into()))?
+            }
+            RegistryFormat::Json => {
+                serde_json::from_str(&content).map_err(|e| RegistryError::FsError(e.into()))?
+            }
+            RegistryFormat::Yaml => serde_yaml_ng::from_str(&content)
+                .map_err(|e| RegistryError::FsError(Error::new(ErrorKind::InvalidData, e)))?,
+        };
+
+        let schema_version = value["schema_version"]
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or_default();
+        let files = value["files"].as_array().map_or(0, Vec::len);
+
+        Ok(RegistryInfo::new(schema_version, files))
+    }
+
+    /// Only a regular file is removed, a directory or a symlink at the registry path is
+    /// left in place and reported instead.
+    #[cfg(test)]
+    fn remove(&self, file_path: &Path) -> Result<(), RegistryError> {
+        let metadata = std::fs::symlink_metadata(file_path).map_err(RegistryError::FsError)?;
+        if !metadata.is_file() {
+            return Err(RegistryError::FsError(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is not a regular file, refusing to remove it",
+                    file_path.display()
+                ),
+            )));
+        }
+
+        std::fs::remove_file(file_path).map_err(RegistryError::FsError)
     }
 }
 
@@ -45,13 +222,170 @@ mod tests {
         let mut registry = Registry::new(Directory::from("businesses"));
         registry.add_file(FileItem::new(FileName::from("test_file.md")));
 
-        let processor = ProcessorAdapter {};
+        let processor = ProcessorAdapter::new();
         let file_path = temp_dir.path().join("registry.json");
         let result = processor.build(file_path.clone(), registry);
         assert!(result.is_ok());
         assert!(file_path.exists());
     }
 
+    #[test]
+    fn test_processor_adapter_build_replaces_atomically() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+        std::fs::write(&file_path, "previous content").unwrap();
+
+        let registry = Registry::new(Directory::from("businesses"));
+        let processor = ProcessorAdapter::new();
+        let result = processor.build(file_path.clone(), registry.clone());
+        assert!(result.is_ok());
+
+        assert!(!temp_dir.path().join("registry.json.tmp").exists());
+        assert_eq!(processor.parse(file_path).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_processor_adapter_yaml_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join(RegistryFormat::Yaml.file_name());
+
+        let mut registry = Registry::new(Directory::from("businesses"));
+        registry.add_file(FileItem::new(FileName::from("order")));
+
+        let processor = ProcessorAdapter::new();
+        processor
+            .build(file_path.clone(), registry.clone())
+            .unwrap();
+        assert!(!temp_dir.path().join("registry.yaml.tmp").exists());
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains("name: order"));
+        assert_eq!(processor.parse(file_path).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_processor_adapter_parse_lenient() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+        std::fs::write(
+            &file_path,
+            r#"{
+                "directory": "businesses",
+                "files": [
+                    { "name": "order,]", "versions": ["1.0.0", "1.1.0",], },
+                ],
+            }"#,
+        )
+        .unwrap();
+
+        assert!(ProcessorAdapter::new().parse(file_path.clone()).is_err());
+
+        let processor = ProcessorAdapter::new().with_lenient(true);
+        let registry = processor.parse(file_path.clone()).unwrap();
+        let file_item = registry.get_file(&FileName::from("order,]")).unwrap();
+        assert_eq!(file_item.versions.len(), 2);
+
+        processor
+            .build(file_path.clone(), registry.clone())
+            .unwrap();
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Registry>(&content).unwrap(),
+            registry
+        );
+    }
+
+    #[test]
+    fn test_processor_adapter_checksum() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+
+        let mut registry = Registry::new(Directory::from("businesses"));
+        registry.add_file(FileItem::new(FileName::from("order")));
+
+        let processor = ProcessorAdapter::new().with_strict(true);
+        processor
+            .build(file_path.clone(), registry.clone())
+            .unwrap();
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains(&registry.checksum().unwrap()));
+        assert_eq!(processor.parse(file_path.clone()).unwrap(), registry);
+
+        // a checksum-less registry is accepted as is
+        std::fs::write(&file_path, serde_json::to_string(&registry).unwrap()).unwrap();
+        assert_eq!(processor.parse(file_path).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_processor_adapter_checksum_tampered() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+
+        let mut registry = Registry::new(Directory::from("businesses"));
+        registry.add_file(FileItem::new(FileName::from("order")));
+        ProcessorAdapter::new()
+            .build(file_path.clone(), registry)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        std::fs::write(&file_path, content.replace("0.1.0", "0.2.0")).unwrap();
+
+        let registry = ProcessorAdapter::new().parse(file_path.clone()).unwrap();
+        assert!(registry.get_file(&FileName::from("order")).is_some());
+
+        let result = ProcessorAdapter::new().with_strict(true).parse(file_path);
+        assert!(matches!(result, Err(RegistryError::ChecksumMismatch(_))));
+    }
+
+    #[test]
+    fn test_processor_adapter_inspect() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+
+        let mut registry = Registry::new(Directory::from("businesses"));
+        registry.add_file(FileItem::new(FileName::from("order")));
+        registry.add_file(FileItem::new(FileName::from("invoice")));
+
+        let processor = ProcessorAdapter::new();
+        processor
+            .build(file_path.clone(), registry.clone())
+            .unwrap();
+        assert_eq!(
+            processor.inspect(file_path.clone()).unwrap(),
+            RegistryInfo {
+                schema_version: REGISTRY_SCHEMA_VERSION,
+                files: 2,
+                migration_pending: false,
+            }
+        );
+
+        // a registry written before the schema version was introduced
+        std::fs::write(&file_path, serde_json::to_string(&registry).unwrap()).unwrap();
+        assert_eq!(
+            processor.inspect(file_path.clone()).unwrap(),
+            RegistryInfo {
+                schema_version: 0,
+                files: 2,
+                migration_pending: true,
+            }
+        );
+
+        // the next write migrates it
+        let registry = processor.parse(file_path.clone()).unwrap();
+        processor.build(file_path.clone(), registry).unwrap();
+        assert!(!processor.inspect(file_path).unwrap().migration_pending);
+    }
+
+    #[test]
+    fn test_strip_trailing_commas() {
+        assert_eq!(strip_trailing_commas("[1, 2 ,\n]"), "[1, 2 \n]");
+        assert_eq!(
+            strip_trailing_commas(r#"{"a": "x\",}",}"#),
+            r#"{"a": "x\",}"}"#
+        );
+    }
+
     // Test ProcessorAdapter parse method
     #[test]
     fn test_processor_adapter_parse() {
@@ -61,7 +395,7 @@ mod tests {
         let mut registry = Registry::new(Directory::from("businesses"));
         registry.add_file(FileItem::new(FileName::from("test_file.md")));
 
-        let processor = ProcessorAdapter {};
+        let processor = ProcessorAdapter::new();
         let build_result = processor.build(file_path.clone(), registry);
         assert!(build_result.is_ok());
 
@@ -82,4 +416,29 @@ mod tests {
             REGISTRY_VERSION_GENESIS.to_string()
         );
     }
+
+    #[test]
+    fn test_processor_adapter_remove() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+        std::fs::write(&file_path, "{}").unwrap();
+
+        let processor = ProcessorAdapter::new();
+        assert!(processor.remove(&file_path).is_ok());
+        assert!(!file_path.exists());
+    }
+
+    #[test]
+    fn test_processor_adapter_remove_not_a_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry.json");
+        std::fs::create_dir(&file_path).unwrap();
+
+        let processor = ProcessorAdapter::new();
+        match processor.remove(&file_path) {
+            Err(RegistryError::FsError(err)) => assert_eq!(err.kind(), ErrorKind::InvalidInput),
+            _ => panic!("Expected FsError"),
+        }
+        assert!(file_path.is_dir());
+    }
 }
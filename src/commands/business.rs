@@ -1,19 +1,71 @@
+use std::collections::BTreeMap;
 use std::env;
+use std::fs::{self, create_dir_all};
+use std::io::{self, Error, ErrorKind, IsTerminal};
+use std::path::{Component, Path, PathBuf};
 
-use clap::{Args, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::builder::NonEmptyStringValueParser;
+use clap::{Args, Subcommand, ValueEnum};
+use serde::Serialize;
+use similar::TextDiff;
+use tracing::warn;
 
 use crate::core::registry::manager::Manager as RegistryManager;
-use crate::core::registry::types::{FileVersion, REGISTRY_VERSION_GENESIS};
+use crate::core::registry::types::{
+    Directory, FileItem, FileLayout, FileLocation, FileName, FileVersion, VersionPart,
+    REGISTRY_SCHEMA_VERSION,
+};
 
 use crate::core::business::app::App as BusinessApp;
-use crate::core::business::types::{BusinessError, Definition, BUSINESS_DIR_NAME};
+use crate::core::business::markdown::DEFAULT_REQUIRED_SECTIONS;
+use crate::core::business::template::render_template;
+use crate::core::business::types::{
+    AnalyzeMode, AnalyzeParameters, Architecture, BusinessError, DefineReport, Definition, Language,
+};
+use crate::core::project::types::{Project as CoreProject, PROJECT_BUSINESS_DIR_NAME};
+use crate::core::types::{validate, CoreError, PathBufWrapper, ToJSON};
 
+use crate::cli::OutputFormat;
 use crate::commands::adapters::business::processor::ProcessorAdapter as BusinessProcessorAdapter;
+use crate::commands::adapters::document::processor::ProcessorAdapter as DocumentProcessorAdapter;
+use crate::commands::adapters::hook::HookAdapter;
 use crate::commands::adapters::path_buf_wrapper::PathBufAdapter;
 use crate::commands::adapters::registry::processor::ProcessorAdapter as RegistryProcessorAdapter;
+use crate::commands::interactive::{ask_define, terminal, DefineAnswers, Prompt};
+use crate::commands::interrupt;
+use crate::commands::project::load_project;
+
+/// Exit code of `business list --fail-on-empty` when nothing is registered, kept apart
+/// from the generic failure code so pipelines can tell both outcomes apart.
+pub(crate) const EMPTY_EXIT_CODE: u8 = 3;
+
+/// The language assumed by `business define` when none is given.
+pub(crate) const DEFAULT_LANGUAGE: &str = "Rust";
+
+/// The architecture assumed by `business define` when none is given.
+pub(crate) const DEFAULT_ARCHITECTURE: &str = "Modular Monolith";
 
 #[derive(Args)]
 pub(crate) struct BusinessArgs {
+    /// The directory holding the business definition files and their registry,
+    /// defaults to the project's `businesses` directory
+    #[arg(long, global = true)]
+    pub businesses_dir: Option<PathBuf>,
+
+    /// The directory holding the registry file, defaults to the businesses directory
+    #[arg(long, global = true)]
+    pub registry_dir: Option<PathBuf>,
+
+    /// Tolerate trailing commas in a hand-edited JSON registry, it's still written as strict JSON
+    #[arg(long, global = true)]
+    pub lenient_registry: bool,
+
+    /// Fail on a registry not matching its checksum, instead of only warning that it was
+    /// modified outside of ddai
+    #[arg(long, global = true)]
+    pub strict_checksum: bool,
+
     #[command(subcommand)]
     pub commands: Business,
 }
@@ -22,21 +74,27 @@ pub(crate) struct BusinessArgs {
 pub(crate) enum Business {
     /// Define a new business file
     Define {
-        /// The name of the business to define
-        #[arg(long, required = true)]
-        business_name: String,
+        /// The name of the business to define, asked for with `--interactive`
+        #[arg(long, required_unless_present = "interactive")]
+        business_name: Option<String>,
 
-        /// The business file version
-        #[arg(long, default_value = REGISTRY_VERSION_GENESIS)]
+        /// The business file version, `0.1.0` when neither this nor `--increment` is given
+        #[arg(long)]
         business_version: Option<String>,
 
-        /// The chosen programming language for the technical architecture stack
-        #[arg(long, default_value = "Rust")]
+        /// Define the next version of an existing business by incrementing its last version
+        #[arg(long, value_enum, conflicts_with = "business_version")]
+        increment: Option<Increment>,
+
+        /// The chosen programming language for the technical architecture stack, `Rust` when
+        /// not given
+        #[arg(long)]
         language: Option<String>,
 
-        /// The name of the architect responsible for the business file
+        /// The name of the architect responsible for the business file, `Modular Monolith`
+        /// when not given
         /// Exampple: "Modular Monolith"
-        #[arg(long, default_value = "Modular Monolith")]
+        #[arg(long)]
         architect: Option<String>,
 
         /// The additional prompt message used to additional context to the LLM models
@@ -50,43 +108,1412 @@ pub(crate) enum Business {
         /// Whether to only output the JSON representation of the business file
         #[arg(long, default_value = "false")]
         only_json: Option<bool>,
+
+        /// Print only the resulting version on success, errors still go to stderr
+        #[arg(long)]
+        quiet: bool,
+
+        /// Print the absolute path of the business file as well, or only that path along
+        /// with `--quiet`
+        #[arg(long)]
+        print_path: bool,
+
+        /// Where the business file is placed, ignored when the business is already registered
+        #[arg(long, value_enum, default_value_t = DirLayout::Nested)]
+        dir_layout: DirLayout,
+
+        /// The directory the business file is written to instead of the businesses directory,
+        /// e.g. to keep it next to the code. It's recorded in the registry, and ignored when
+        /// the business is already registered
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+
+        /// A shell command run once the registry changed, it gets the `DDAI_DEFINITION` and
+        /// `DDAI_VERSION` env vars. Overrides the project's `registry.on_change` setting
+        #[arg(long, value_name = "COMMAND")]
+        on_change: Option<String>,
+
+        /// Fail when the on-change hook fails, instead of only warning about it
+        #[arg(long)]
+        strict_hook: bool,
+
+        /// Fail on an unusual language and architecture pairing, instead of only warning
+        /// about it
+        #[arg(long)]
+        strict_pairing: bool,
+
+        /// Fail when `--output-dir` is outside of the current directory
+        #[arg(long)]
+        restrict_output_dir: bool,
+
+        /// Only validate the inputs and exit, nothing is written
+        #[arg(long)]
+        validate_only: bool,
+
+        /// Succeed without rewriting anything when the business file version already exists
+        #[arg(long)]
+        silent_duplicate: bool,
+
+        /// The domain grouping the business, e.g. `billing`, it replaces the domain of an
+        /// already registered business
+        #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+        domain: Option<String>,
+
+        /// Ask for the name, version, language and architecture not given as flags, then
+        /// confirm before defining anything
+        #[arg(long)]
+        interactive: bool,
+
+        /// A template the new business file starts from, its `{{name}}`, `{{version}}`,
+        /// `{{language}}` and `{{architecture}}` placeholders are substituted
+        #[arg(long, value_name = "PATH")]
+        from_template_file: Option<PathBuf>,
     },
+
+    /// Check every version of a business file for the required markdown sections
+    Lint {
+        /// The name of the business to lint
+        business_name: String,
+
+        /// A section heading every version must contain, can be repeated.
+        /// Overrides the project's `lint.required_sections` setting
+        #[arg(long = "section")]
+        sections: Vec<String>,
+    },
+
+    /// Show a business file registry entry, or the content of one of its versions
+    Show {
+        /// The name of the business to show
+        business_name: String,
+
+        /// The business file version to print, defaults to the latest one
+        #[arg(long)]
+        business_version: Option<String>,
+
+        /// Print the latest version starting with this dotted prefix, e.g. `1.2` for `1.2.x`
+        #[arg(long, conflicts_with = "business_version")]
+        version_prefix: Option<String>,
+
+        /// Print the content of the business file instead of its metadata
+        #[arg(long)]
+        raw: bool,
+
+        /// Replace invalid UTF-8 in the business file instead of failing
+        #[arg(long)]
+        lossy: bool,
+
+        /// Print a unified diff between the contents of two registered versions
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["FROM", "TO"],
+            conflicts_with_all = ["business_version", "version_prefix", "raw"]
+        )]
+        diff: Option<Vec<String>>,
+
+        /// Never color the diff, which is only colored when stdout is a terminal
+        #[arg(long)]
+        no_color: bool,
+    },
+
+    /// Estimate the size, and optionally the cost, of the prompt analyzing a business file
+    /// version, without calling any model
+    Estimate {
+        /// The name of the business to estimate the prompt of
+        business_name: String,
+
+        /// The business file version the prompt is built from, defaults to the newest one
+        #[arg(long)]
+        business_version: Option<String>,
+
+        /// The programming language of the technical architecture stack, `Rust` when not given
+        #[arg(long)]
+        language: Option<String>,
+
+        /// The architecture style, `Modular Monolith` when not given
+        #[arg(long)]
+        architect: Option<String>,
+
+        /// Additional context appended to the prompt
+        #[arg(long)]
+        additional_prompt: Option<String>,
+
+        /// Ask for C4 model diagrams in the prompt
+        #[arg(long)]
+        use_c4: bool,
+
+        /// Add a summary of the other registered business files to the prompt
+        #[arg(long)]
+        include_registry: bool,
+
+        /// Estimate the prompt reviewing the business file for gaps, instead of the one
+        /// designing its architecture
+        #[arg(long)]
+        review: bool,
+
+        /// The price in dollars per million prompt tokens, adds the cost to the estimate
+        #[arg(long, value_name = "USD")]
+        price: Option<f64>,
+    },
+
+    /// Print the registered versions of a business file, oldest first
+    Versions {
+        /// The name of the business to list the versions of
+        business_name: String,
+    },
+
+    /// Export the whole registry along with every business file it references into another
+    /// directory, e.g. for backups or migrations
+    Export {
+        /// The directory receiving the export, created when missing
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Report the business files found on disk but missing from the registry, and the
+    /// duplicate entries of the registry
+    Doctor {
+        /// Register the untracked business files instead of only reporting them
+        #[arg(long)]
+        fix_orphans: bool,
+
+        /// Merge the duplicate business files of the registry and drop their duplicate
+        /// versions instead of only reporting them. The registered versions stay the same,
+        /// so the on-change hook doesn't run for it
+        #[arg(long)]
+        repair: bool,
+
+        /// A shell command run for each business file version registered by
+        /// `--fix-orphans`, see `business define --on-change`
+        #[arg(long, value_name = "COMMAND")]
+        on_change: Option<String>,
+
+        /// Fail when the on-change hook fails, instead of only warning about it
+        #[arg(long)]
+        strict_hook: bool,
+    },
+
+    /// Show how many business files are registered per number of versions
+    Stats,
+
+    /// Print the schema version and file count of the registry file as found on disk, and
+    /// whether its next write migrates it
+    RegistryInfo,
+
+    /// Validate the registry content, its directory, files and versions, and report every
+    /// problem with its location. The business files themselves are not checked
+    RegistryValidate,
+
+    /// List the registered business files and their versions
+    List {
+        /// Exit with a distinct non-zero code when no business file is registered
+        #[arg(long)]
+        fail_on_empty: bool,
+
+        /// Only list the business files of this domain
+        #[arg(long)]
+        domain: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum DirLayout {
+    /// One directory per business, holding `{version}.md`
+    Nested,
+
+    /// `{name}-{version}.md` directly under the businesses directory
+    Flat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum Increment {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl From<Increment> for VersionPart {
+    fn from(increment: Increment) -> Self {
+        match increment {
+            Increment::Major => VersionPart::Major,
+            Increment::Minor => VersionPart::Minor,
+            Increment::Patch => VersionPart::Patch,
+        }
+    }
+}
+
+impl From<DirLayout> for FileLayout {
+    fn from(layout: DirLayout) -> Self {
+        match layout {
+            DirLayout::Nested => FileLayout::Nested,
+            DirLayout::Flat => FileLayout::Flat,
+        }
+    }
+}
+
+impl BusinessArgs {
+    pub(crate) fn quiet(&self) -> bool {
+        match &self.commands {
+            Business::Define { quiet, .. } => *quiet,
+            Business::Lint { .. }
+            | Business::Show { .. }
+            | Business::Estimate { .. }
+            | Business::Versions { .. }
+            | Business::Export { .. }
+            | Business::Doctor { .. }
+            | Business::Stats
+            | Business::RegistryInfo
+            | Business::RegistryValidate
+            | Business::List { .. } => false,
+        }
+    }
+
+    pub(crate) fn silent_duplicate(&self) -> bool {
+        match &self.commands {
+            Business::Define {
+                silent_duplicate, ..
+            } => *silent_duplicate,
+            Business::Lint { .. }
+            | Business::Show { .. }
+            | Business::Estimate { .. }
+            | Business::Versions { .. }
+            | Business::Export { .. }
+            | Business::Doctor { .. }
+            | Business::Stats
+            | Business::RegistryInfo
+            | Business::RegistryValidate
+            | Business::List { .. } => false,
+        }
+    }
+
+    pub(crate) fn lossy(&self) -> bool {
+        match &self.commands {
+            Business::Show { lossy, .. } => *lossy,
+            Business::Define { .. }
+            | Business::Lint { .. }
+            | Business::Estimate { .. }
+            | Business::Versions { .. }
+            | Business::Export { .. }
+            | Business::Doctor { .. }
+            | Business::Stats
+            | Business::RegistryInfo
+            | Business::RegistryValidate
+            | Business::List { .. } => false,
+        }
+    }
+}
+
+/// `RegistryStats` is the summary printed by `business stats`.
+#[derive(Debug, Serialize)]
+struct RegistryStats {
+    files: usize,
+    versions: usize,
+
+    /// The number of business files keyed by their number of versions
+    histogram: BTreeMap<usize, usize>,
+
+    /// The business file a version was most recently added to, absent when no addition
+    /// time was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_updated: Option<LatestUpdated>,
+}
+
+#[derive(Debug, Serialize)]
+struct LatestUpdated {
+    name: FileName,
+    updated_at: DateTime<Utc>,
+}
+
+impl ToJSON for RegistryStats {}
+
+/// Builds the analyze parameters described by the `define` arguments.
+///
+/// An empty additional prompt is the flag's default, so it counts as no prompt at all.
+fn define_parameters(
+    definition: Definition,
+    version: FileVersion,
+    language: Option<String>,
+    architect: Option<String>,
+    additional_prompt: Option<String>,
+    use_c4: bool,
+    only_json: bool,
+) -> AnalyzeParameters {
+    let params = AnalyzeParameters::new(
+        definition,
+        version,
+        language.map(Language::from),
+        architect.map(Architecture::from),
+    )
+    .with_use_c4(use_c4)
+    .with_only_json(only_json);
+
+    match additional_prompt.filter(|prompt| !prompt.is_empty()) {
+        Some(prompt) => params.with_additional_prompt(prompt),
+        None => params,
+    }
+}
+
+/// Resolves a directory against the current one, its parent has to exist already.
+fn resolve_dir(current_dir: &Path, dir: PathBuf, label: &str) -> Result<PathBuf, BusinessError> {
+    let dir = current_dir.join(dir);
+
+    let parent_exists = dir.parent().is_some_and(|parent| parent.is_dir());
+    if !parent_exists {
+        return Err(BusinessError::FsError(Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "Parent of the {} directory is missing: {}",
+                label,
+                dir.display()
+            ),
+        )));
+    }
+
+    Ok(dir)
+}
+
+/// Groups the files by domain, the ungrouped ones first then each domain by name, files
+/// keeping their order within a group.
+fn group_by_domain(files: &[FileItem]) -> BTreeMap<Option<&str>, Vec<&FileItem>> {
+    files.iter().fold(BTreeMap::new(), |mut groups, file| {
+        groups
+            .entry(file.domain.as_deref())
+            .or_insert_with(Vec::new)
+            .push(file);
+        groups
+    })
+}
+
+/// Renders a unified diff between two version contents, colored with ANSI escapes when
+/// asked to, the file headers are labelled after the versions.
+fn render_diff(from: &FileVersion, to: &FileVersion, old: &str, new: &str, color: bool) -> String {
+    let diff = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&from.to_string(), &to.to_string())
+        .to_string();
+
+    if !color {
+        return diff;
+    }
+
+    diff.split_inclusive('\n')
+        .map(|line| {
+            let style = match line {
+                _ if line.starts_with("---") || line.starts_with("+++") => "\x1b[1m",
+                _ if line.starts_with("@@") => "\x1b[36m",
+                _ if line.starts_with('-') => "\x1b[31m",
+                _ if line.starts_with('+') => "\x1b[32m",
+                _ => return line.to_string(),
+            };
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line, ""),
+            };
+            format!("{}{}\x1b[0m{}", style, content, newline)
+        })
+        .collect()
+}
+
+/// Resolves the `.` and `..` components of a path without touching the filesystem.
+fn lexical(path: &Path) -> PathBuf {
+    path.components()
+        .fold(PathBuf::new(), |mut resolved, component| {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => _ = resolved.pop(),
+                component => resolved.push(component),
+            }
+            resolved
+        })
+}
+
+fn outside_output_root(dir: &Path, root: &Path) -> BusinessError {
+    BusinessError::FsError(Error::new(
+        ErrorKind::PermissionDenied,
+        format!(
+            "Output directory {} is outside of {}",
+            dir.display(),
+            root.display()
+        ),
+    ))
+}
+
+/// Checks lexically that the `--output-dir` of a definition is inside the current
+/// directory, so nothing has to exist yet.
+fn check_output_root(current_dir: &Path, dir: &Path) -> Result<(), BusinessError> {
+    let resolved = lexical(&current_dir.join(dir));
+    if !resolved.starts_with(lexical(current_dir)) {
+        return Err(outside_output_root(&resolved, current_dir));
+    }
+
+    Ok(())
+}
+
+/// Creates the `--output-dir` of a definition when needed and checks it's writable.
+///
+/// Under `restrict`, the directory also has to be inside the current one. It's returned
+/// as given, so the registry records it relative to the current directory.
+fn check_output_dir(
+    current_dir: &Path,
+    dir: PathBuf,
+    restrict: bool,
+) -> Result<PathBuf, BusinessError> {
+    let resolved = current_dir.join(&dir);
+
+    // checked lexically first so nothing gets created outside, then once canonicalized
+    // below to catch symlinks
+    if restrict {
+        check_output_root(current_dir, &dir)?;
+    }
+
+    create_dir_all(&resolved).map_err(BusinessError::FsError)?;
+
+    let metadata = resolved.metadata().map_err(BusinessError::FsError)?;
+    if metadata.permissions().readonly() {
+        return Err(BusinessError::FsError(Error::new(
+            ErrorKind::PermissionDenied,
+            format!("Output directory is not writable: {}", resolved.display()),
+        )));
+    }
+
+    if restrict {
+        let root = current_dir.canonicalize().map_err(BusinessError::FsError)?;
+        let canonical = resolved.canonicalize().map_err(BusinessError::FsError)?;
+        if !canonical.starts_with(&root) {
+            return Err(outside_output_root(&canonical, &root));
+        }
+    }
+
+    Ok(dir)
+}
+
+/// `define_output` gives the lines printed by a define in text format, `domain` is only
+/// set when the define changed the domain of the definition.
+///
+/// The path of the file is only printed when the current directory is given, relative
+/// paths are resolved against it, and with `quiet` it replaces the version.
+fn define_output(
+    definition: &Definition,
+    report: &DefineReport,
+    domain: Option<&str>,
+    quiet: bool,
+    current_dir: Option<&Path>,
+) -> Vec<String> {
+    let mut lines = vec![];
+    match (quiet, report.added, domain) {
+        (true, _, _) if current_dir.is_some() => {}
+        (true, _, _) => lines.push(report.version.to_string()),
+        (false, true, _) => lines.push(format!("added {} {}", definition.stem(), report.version)),
+        (false, false, Some(domain)) => lines.push(format!(
+            "{} {} already existed, moved to domain {}",
+            definition.stem(),
+            report.version,
+            domain
+        )),
+        (false, false, None) => lines.push(format!(
+            "{} {} already existed",
+            definition.stem(),
+            report.version
+        )),
+    }
+    if let Some(current_dir) = current_dir {
+        lines.push(current_dir.join(&report.path).display().to_string());
+    }
+
+    lines
+}
+
+/// Reads a `--from-template-file` template, a missing one being reported as such rather
+/// than as a bare filesystem error.
+fn read_template(path: &Path) -> Result<String, BusinessError> {
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        ErrorKind::NotFound => {
+            BusinessError::TemplateError(format!("template {} not found", path.display()))
+        }
+        _ => BusinessError::FsError(err),
+    })
 }
 
 type TRegistryProcessor = RegistryProcessorAdapter;
 type TPathBufWrapper = PathBufAdapter;
-type TBusinessProcessor = BusinessProcessorAdapter<TPathBufWrapper>;
+type TDocumentProcessor = DocumentProcessorAdapter;
+type TBusinessProcessor = BusinessProcessorAdapter<TPathBufWrapper, TDocumentProcessor>;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Handler {
     app: BusinessApp<TBusinessProcessor, TRegistryProcessor, TPathBufWrapper>,
+    project: Option<CoreProject>,
 }
 
 impl Handler {
-    pub(crate) fn new() -> Result<Self, BusinessError> {
-        let current_dir = env::current_dir().map_err(|err| BusinessError::FsError(err.into()))?;
-        
-        let registry_path_buf = PathBufAdapter::new(current_dir.join(BUSINESS_DIR_NAME));
-        let registry_processor = RegistryProcessorAdapter::new();
-        let registry_manager = RegistryManager::new(registry_processor, registry_path_buf);
+    /// `new` builds the handler on top of the businesses and registry directories of the args.
+    ///
+    /// When no businesses directory is given, it falls back to the project layout, which is
+    /// [`PROJECT_BUSINESS_DIR_NAME`] under the current directory. The registry lives in the
+    /// businesses directory unless its own directory is given. Relative paths are resolved
+    /// against the current directory.
+    pub(crate) fn new(args: &BusinessArgs) -> Result<Self, BusinessError> {
+        let current_dir = env::current_dir().map_err(BusinessError::FsError)?;
+        let businesses_dir = resolve_dir(
+            &current_dir,
+            args.businesses_dir
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(PROJECT_BUSINESS_DIR_NAME)),
+            "businesses",
+        )?;
+        let registry_dir = match args.registry_dir.clone() {
+            Some(dir) => resolve_dir(&current_dir, dir, "registry")?,
+            None => businesses_dir.clone(),
+        };
+
+        let project = load_project(&DocumentProcessorAdapter::new(), &current_dir)?;
+        let registry_config = project
+            .as_ref()
+            .and_then(|project| project.registry.clone())
+            .unwrap_or_default();
 
-        let business_path_buf = PathBufAdapter::new(current_dir.join(BUSINESS_DIR_NAME));
-        let business_processor = BusinessProcessorAdapter::new(business_path_buf);
-        let business_app = BusinessApp::new(business_processor, registry_manager);
+        // the registry is labelled after the businesses directory, wherever it's kept
+        let registry_path_buf = PathBufAdapter::new(registry_dir);
+        let registry_processor = RegistryProcessorAdapter::new()
+            .with_lenient(args.lenient_registry)
+            .with_strict(args.strict_checksum);
+        let registry_manager = RegistryManager::new(registry_processor, registry_path_buf)
+            .with_max_files(registry_config.max_files)
+            .with_max_versions(registry_config.max_versions)
+            .with_format(registry_config.format.unwrap_or_default())
+            .with_case_sensitive(registry_config.case_sensitive.unwrap_or_default());
+        let registry_manager = match businesses_dir.dir_name() {
+            Some(label) => registry_manager.with_directory(Directory::from(label)),
+            None => registry_manager,
+        };
 
-        Ok(Self { app: business_app })
+        let business_path_buf = PathBufAdapter::new(businesses_dir);
+        let business_processor =
+            BusinessProcessorAdapter::new(business_path_buf, DocumentProcessorAdapter::new())
+                .with_lossy(args.lossy());
+        let business_app = BusinessApp::new(business_processor, registry_manager)
+            .with_silent_duplicate(args.silent_duplicate());
+
+        Ok(Self {
+            app: business_app,
+            project,
+        })
     }
 
-    pub(crate) fn define(&self, args: BusinessArgs) -> Result<(), BusinessError> {
+    pub(crate) fn handle(
+        &self,
+        args: BusinessArgs,
+        output: OutputFormat,
+    ) -> Result<(), BusinessError> {
         match args.commands {
             Business::Define {
                 business_name,
                 business_version,
+                increment,
+                language,
+                architect,
+                additional_prompt,
+                use_c4,
+                only_json,
+                quiet,
+                print_path,
+                dir_layout,
+                output_dir,
+                on_change,
+                strict_hook,
+                strict_pairing,
+                restrict_output_dir,
+                validate_only,
+                interactive,
+                domain,
+                from_template_file,
                 ..
-            } => self.app.define(
-                Definition::from(business_name),
-                business_version.map(|val| FileVersion::from(val)),
-            ),
+            } => {
+                let answers = DefineAnswers {
+                    business_name,
+                    business_version,
+                    increment,
+                    language,
+                    architect,
+                };
+                let DefineAnswers {
+                    business_name,
+                    business_version,
+                    increment,
+                    language,
+                    architect,
+                } = match interactive {
+                    true => ask_define(&terminal()?, answers)?,
+                    false => answers,
+                };
+                let language = language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+                let architect = architect.unwrap_or_else(|| DEFAULT_ARCHITECTURE.to_string());
+
+                let definition = Definition::from(business_name.unwrap_or_default());
+                let version = match increment {
+                    Some(part) => self.app.next_version(definition.clone(), part.into())?,
+                    None => business_version
+                        .map(FileVersion::from)
+                        .unwrap_or_else(FileVersion::new),
+                };
+
+                let content = match from_template_file {
+                    Some(path) => render_template(
+                        &read_template(&path)?,
+                        &[
+                            ("name", definition.stem()),
+                            ("version", version.as_str()),
+                            ("language", &language),
+                            ("architecture", &architect),
+                        ],
+                    )?,
+                    None => String::new(),
+                };
+
+                // every input check runs before `--validate-only` returns, so it reports
+                // exactly what a real define would reject
+                let params = define_parameters(
+                    definition.clone(),
+                    version.clone(),
+                    Some(language.clone()),
+                    Some(architect.clone()),
+                    additional_prompt,
+                    use_c4.unwrap_or_default(),
+                    only_json.unwrap_or_default(),
+                );
+                validate(&params.with_strict(strict_pairing))?;
+
+                let current_dir = env::current_dir().map_err(BusinessError::FsError)?;
+                if let (Some(dir), true) = (&output_dir, restrict_output_dir) {
+                    check_output_root(&current_dir, dir)?;
+                }
+
+                if validate_only {
+                    if !quiet {
+                        println!("{} {} is valid", definition.stem(), version);
+                    }
+                    return Ok(());
+                }
+
+                if interactive {
+                    let confirmed = terminal()?.confirm(&format!(
+                        "Define {} {} ({}, {})?",
+                        definition.stem(),
+                        version,
+                        language,
+                        architect
+                    ))?;
+                    if !confirmed {
+                        println!("cancelled, nothing was defined");
+                        return Ok(());
+                    }
+                }
+
+                let output_dir = match output_dir {
+                    Some(dir) => Some(check_output_dir(&current_dir, dir, restrict_output_dir)?),
+                    None => None,
+                };
+                let location =
+                    FileLocation::from(FileLayout::from(dir_layout)).with_dir(output_dir);
+
+                let (report, domain) = interrupt::critical(|| {
+                    let report = self.app.define(
+                        definition.clone(),
+                        Some(version.clone()),
+                        location.clone(),
+                        &content,
+                    )?;
+                    // only a domain which actually changed is reported
+                    let domain = match domain {
+                        Some(domain) => self
+                            .app
+                            .set_domain(definition.clone(), domain.clone())?
+                            .then_some(domain),
+                        None => None,
+                    };
+                    Ok::<_, BusinessError>((report, domain))
+                })?;
+                // the command stops once its writes are done, neither the hook nor the
+                // output run after an interrupt
+                if interrupt::interrupted() {
+                    return Ok(());
+                }
+
+                if report.added || domain.is_some() {
+                    self.on_change(on_change, &definition, &report.version, strict_hook)?;
+                }
+
+                if output == OutputFormat::Json {
+                    let file_item = self.app.get(definition)?;
+                    println!("{}", file_item.to_json()?);
+                    return Ok(());
+                }
+
+                for line in define_output(
+                    &definition,
+                    &report,
+                    domain.as_deref(),
+                    quiet,
+                    print_path.then_some(current_dir.as_path()),
+                ) {
+                    println!("{}", line);
+                }
+
+                Ok(())
+            }
+            Business::Lint {
+                business_name,
+                sections,
+            } => self.lint(business_name, sections),
+            Business::Estimate {
+                business_name,
+                business_version,
+                language,
+                architect,
+                additional_prompt,
+                use_c4,
+                include_registry,
+                review,
+                price,
+            } => {
+                let definition = Definition::from(business_name);
+                let version = self
+                    .app
+                    .resolve_version(definition.clone(), business_version.map(FileVersion::from))?;
+                let params = define_parameters(
+                    definition,
+                    version,
+                    Some(language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())),
+                    Some(architect.unwrap_or_else(|| DEFAULT_ARCHITECTURE.to_string())),
+                    additional_prompt,
+                    use_c4,
+                    false,
+                )
+                .with_include_registry(include_registry)
+                .with_mode(match review {
+                    true => AnalyzeMode::Review,
+                    false => AnalyzeMode::Generate,
+                });
+                self.estimate(params, price, output)
+            }
+            Business::Versions { business_name } => {
+                self.versions(Definition::from(business_name), output)
+            }
+            Business::List {
+                fail_on_empty,
+                domain,
+            } => self.list(fail_on_empty, domain, output),
+            Business::Stats => self.stats(output),
+            Business::RegistryInfo => self.registry_info(output),
+            Business::RegistryValidate => self.registry_validate(),
+            Business::Export { out } => self.export_registry(out),
+            Business::Doctor {
+                fix_orphans,
+                repair,
+                on_change,
+                strict_hook,
+            } => self.doctor(fix_orphans, repair, on_change, strict_hook),
+            Business::Show {
+                business_name,
+                business_version,
+                version_prefix,
+                raw,
+                diff,
+                no_color,
+                ..
+            } => {
+                let definition = Definition::from(business_name);
+                if let Some([from, to]) = diff.as_deref() {
+                    let color = !no_color && io::stdout().is_terminal();
+                    return self.diff(
+                        definition,
+                        FileVersion::from(from.clone()),
+                        FileVersion::from(to.clone()),
+                        color,
+                    );
+                }
+
+                let version = match (business_version, version_prefix) {
+                    (Some(version), _) => Some(FileVersion::from(version)),
+                    (None, Some(prefix)) => {
+                        Some(self.app.latest_with_prefix(definition.clone(), &prefix)?)
+                    }
+                    (None, None) => None,
+                };
+
+                self.show(definition, version, raw, output)
+            }
+        }
+    }
+
+    /// Runs the on-change hook, the flag wins over the project config.
+    ///
+    /// A failing hook is only a warning unless `strict` is set, the registry change
+    /// itself is already committed at this point.
+    fn on_change(
+        &self,
+        command: Option<String>,
+        definition: &Definition,
+        version: &FileVersion,
+        strict: bool,
+    ) -> Result<(), BusinessError> {
+        let command = command.or_else(|| {
+            self.project
+                .as_ref()
+                .and_then(|project| project.registry.as_ref())
+                .and_then(|registry| registry.on_change.clone())
+        });
+
+        let Some(command) = command else {
+            return Ok(());
+        };
+
+        match HookAdapter::new(command).run(definition.stem(), version) {
+            Err(err) if !strict => {
+                warn!("{}", err);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn estimate(
+        &self,
+        params: AnalyzeParameters,
+        price: Option<f64>,
+        output: OutputFormat,
+    ) -> Result<(), BusinessError> {
+        let definition = params.definition.clone();
+        let version = params.version.clone();
+        let estimate = self.app.estimate(params, price)?;
+
+        match output {
+            OutputFormat::Text => {
+                let cost = estimate
+                    .cost
+                    .map(|cost| format!(", about ${:.4}", cost))
+                    .unwrap_or_default();
+                println!(
+                    "{} {}: about {} prompt tokens{}",
+                    definition.stem(),
+                    version,
+                    estimate.tokens,
+                    cost
+                );
+            }
+            OutputFormat::Json => println!("{}", estimate.to_json()?),
+        }
+
+        Ok(())
+    }
+
+    fn versions(&self, definition: Definition, output: OutputFormat) -> Result<(), BusinessError> {
+        let versions = self.app.versions(definition)?;
+
+        match output {
+            OutputFormat::Text => {
+                for version in &versions {
+                    println!("{}", version);
+                }
+            }
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&versions).map_err(CoreError::JSONError)?;
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn doctor(
+        &self,
+        fix_orphans: bool,
+        repair: bool,
+        on_change: Option<String>,
+        strict_hook: bool,
+    ) -> Result<(), BusinessError> {
+        let duplicates = interrupt::critical(|| self.app.deduplicate(repair))?;
+        if interrupt::interrupted() {
+            return Ok(());
+        }
+        for duplicate in &duplicates {
+            match repair {
+                true => println!("removed {}", duplicate),
+                false => println!("{}", duplicate),
+            }
+        }
+
+        let orphans = self.app.orphans()?;
+        if orphans.is_empty() {
+            println!("no orphan business files");
+            return Ok(());
+        }
+
+        for file in &orphans {
+            if fix_orphans {
+                let added = interrupt::critical(|| self.app.register(file))?;
+                if interrupt::interrupted() {
+                    return Ok(());
+                }
+                if added {
+                    self.on_change(
+                        on_change.clone(),
+                        &file.definition,
+                        &file.version,
+                        strict_hook,
+                    )?;
+                }
+                println!("registered {} {}", file.definition.stem(), file.version);
+            } else {
+                println!("orphan {} {}", file.definition.stem(), file.version);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn export_registry(&self, out: PathBuf) -> Result<(), BusinessError> {
+        let current_dir = env::current_dir().map_err(BusinessError::FsError)?;
+        let out = current_dir.join(out);
+        create_dir_all(&out).map_err(BusinessError::FsError)?;
+
+        let report = interrupt::critical(|| self.app.export_registry(out.clone()))?;
+        if interrupt::interrupted() {
+            return Ok(());
+        }
+        println!(
+            "exported {} business files to {}",
+            report.exported,
+            out.display()
+        );
+
+        if !report.dangling.is_empty() {
+            return Err(BusinessError::DanglingEntry(report.dangling.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    fn list(
+        &self,
+        fail_on_empty: bool,
+        domain: Option<String>,
+        output: OutputFormat,
+    ) -> Result<(), BusinessError> {
+        let files = self.app.list(domain.as_deref())?;
+
+        match output {
+            OutputFormat::Text if files.is_empty() => println!("no businesses"),
+            OutputFormat::Text => {
+                for (domain, files) in group_by_domain(&files) {
+                    if let Some(domain) = domain {
+                        println!("[{}]", domain);
+                    }
+                    for file in files {
+                        let versions: Vec<&str> =
+                            file.versions.iter().map(|v| v.as_str()).collect();
+                        println!("{}: {}", file.name.as_str(), versions.join(", "));
+                    }
+                }
+            }
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&files).map_err(CoreError::JSONError)?;
+                println!("{}", json);
+            }
+        }
+
+        if fail_on_empty && files.is_empty() {
+            return Err(BusinessError::NoDefinitions);
+        }
+
+        Ok(())
+    }
+
+    fn registry_info(&self, output: OutputFormat) -> Result<(), BusinessError> {
+        let info = self.app.registry_info()?;
+
+        match (output, info) {
+            (OutputFormat::Text, None) => println!("no registry"),
+            (OutputFormat::Text, Some(info)) => {
+                println!("schema_version: {}", info.schema_version);
+                println!("files: {}", info.files);
+                if info.migration_pending {
+                    println!(
+                        "migration: pending, the next write upgrades it to version {}",
+                        REGISTRY_SCHEMA_VERSION
+                    );
+                } else {
+                    println!("migration: none");
+                }
+            }
+            (OutputFormat::Json, None) => println!("null"),
+            (OutputFormat::Json, Some(info)) => println!("{}", info.to_json()?),
+        }
+
+        Ok(())
+    }
+
+    fn registry_validate(&self) -> Result<(), BusinessError> {
+        let problems = self.app.registry_problems()?;
+        if problems.is_empty() {
+            println!("registry is valid");
+            return Ok(());
+        }
+
+        for problem in &problems {
+            println!("{}: {}", problem.location, problem.error);
+        }
+
+        let unit = if problems.len() == 1 {
+            "problem"
+        } else {
+            "problems"
+        };
+        Err(BusinessError::InvalidRegistry(format!(
+            "{} {} found",
+            problems.len(),
+            unit
+        )))
+    }
+
+    fn stats(&self, output: OutputFormat) -> Result<(), BusinessError> {
+        let histogram = self.app.version_histogram()?;
+        let stats = RegistryStats {
+            files: histogram.values().sum(),
+            versions: histogram
+                .iter()
+                .map(|(versions, files)| versions * files)
+                .sum(),
+            histogram,
+            latest_updated: self.app.latest_updated()?.and_then(|file_item| {
+                file_item.updated_at.map(|updated_at| LatestUpdated {
+                    name: file_item.name,
+                    updated_at,
+                })
+            }),
+        };
+
+        match output {
+            OutputFormat::Text => {
+                println!("files: {}", stats.files);
+                println!("versions: {}", stats.versions);
+                for (versions, files) in &stats.histogram {
+                    let unit = if *versions == 1 {
+                        "version"
+                    } else {
+                        "versions"
+                    };
+                    println!("with {} {}: {}", versions, unit, files);
+                }
+                if let Some(latest) = &stats.latest_updated {
+                    println!(
+                        "latest_updated: {} at {}",
+                        latest.name.as_str(),
+                        latest.updated_at.to_rfc3339()
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let json = stats.to_json()?;
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn show(
+        &self,
+        definition: Definition,
+        version: Option<FileVersion>,
+        raw: bool,
+        output: OutputFormat,
+    ) -> Result<(), BusinessError> {
+        if raw {
+            let (_, content) = self.app.read(definition, version)?;
+            print!("{}", content);
+            return Ok(());
+        }
+
+        let file_item = self.app.get(definition)?;
+        match output {
+            OutputFormat::Text => {
+                let versions: Vec<String> =
+                    file_item.versions.iter().map(|v| v.to_string()).collect();
+
+                println!("name: {}", file_item.name.as_str());
+                println!("versions: {}", versions.join(", "));
+                if let Some(latest) = file_item.get_last_version() {
+                    println!("latest: {}", latest);
+                }
+            }
+            OutputFormat::Json => println!("{}", file_item.to_json()?),
+        }
+
+        Ok(())
+    }
+
+    fn diff(
+        &self,
+        definition: Definition,
+        from: FileVersion,
+        to: FileVersion,
+        color: bool,
+    ) -> Result<(), BusinessError> {
+        let (_, old) = self.app.read(definition.clone(), Some(from.clone()))?;
+        let (_, new) = self.app.read(definition, Some(to.clone()))?;
+
+        print!("{}", render_diff(&from, &to, &old, &new, color));
+        Ok(())
+    }
+
+    /// Resolves the required sections: flags first, then the project config, then the defaults.
+    fn required_sections(&self, sections: Vec<String>) -> Vec<String> {
+        if !sections.is_empty() {
+            return sections;
+        }
+
+        let configured = self
+            .project
+            .as_ref()
+            .and_then(|project| project.lint.as_ref())
+            .map(|lint| lint.required_sections.clone())
+            .filter(|sections| !sections.is_empty());
+
+        configured.unwrap_or_else(|| {
+            DEFAULT_REQUIRED_SECTIONS
+                .iter()
+                .map(|section| section.to_string())
+                .collect()
+        })
+    }
+
+    fn lint(&self, business_name: String, sections: Vec<String>) -> Result<(), BusinessError> {
+        let required = self.required_sections(sections);
+        let reports = self.app.lint(Definition::from(business_name), &required)?;
+
+        for report in &reports {
+            if report.is_ok() {
+                println!("{}: ok", report.version);
+            } else {
+                println!("{}: missing {}", report.version, report.missing.join(", "));
+            }
+        }
+
+        let failed = reports.iter().filter(|report| !report.is_ok()).count();
+        if failed > 0 {
+            return Err(BusinessError::LintFailed(format!(
+                "{} of {} versions are missing required sections",
+                failed,
+                reports.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::registry::memory::{MemoryDir, MemoryProcessor};
+
+    #[test]
+    fn test_check_output_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path().join("project");
+        std::fs::create_dir(&current_dir).unwrap();
+
+        let dir = check_output_dir(&current_dir, PathBuf::from("src/order"), true).unwrap();
+        assert_eq!(dir, PathBuf::from("src/order"));
+        assert!(current_dir.join("src/order").is_dir());
+
+        let outside = PathBuf::from("../shared");
+        match check_output_dir(&current_dir, outside.clone(), true) {
+            Err(BusinessError::FsError(err)) => {
+                assert_eq!(err.kind(), ErrorKind::PermissionDenied)
+            }
+            _ => panic!("Expected FsError"),
+        }
+        assert!(!temp_dir.path().join("shared").exists());
+        assert!(check_output_dir(&current_dir, outside, false).is_ok());
+    }
+
+    #[test]
+    fn test_check_output_root() {
+        let current_dir = Path::new("/project");
+        assert!(check_output_root(current_dir, Path::new("src/../order")).is_ok());
+        assert!(check_output_root(current_dir, Path::new("/project/order")).is_ok());
+
+        for dir in ["../shared", "/elsewhere", "src/../../shared"] {
+            match check_output_root(current_dir, Path::new(dir)) {
+                Err(BusinessError::FsError(err)) => {
+                    assert_eq!(err.kind(), ErrorKind::PermissionDenied)
+                }
+                _ => panic!("Expected FsError for {}", dir),
+            }
+        }
+        // only checked, nothing is created
+        assert!(!Path::new("/project").exists());
+    }
+
+    #[test]
+    fn test_group_by_domain() {
+        let mut invoice = FileItem::new(FileName::from("invoice"));
+        invoice.domain = Some("billing".to_string());
+        let mut parcel = FileItem::new(FileName::from("parcel"));
+        parcel.domain = Some("shipping".to_string());
+        let files = vec![
+            parcel.clone(),
+            FileItem::new(FileName::from("order")),
+            invoice.clone(),
+        ];
+
+        let groups: Vec<(Option<&str>, Vec<&str>)> = group_by_domain(&files)
+            .into_iter()
+            .map(|(domain, files)| (domain, files.iter().map(|f| f.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (None, vec!["order"]),
+                (Some("billing"), vec!["invoice"]),
+                (Some("shipping"), vec!["parcel"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let from = FileVersion::from("0.1.0");
+        let to = FileVersion::from("0.2.0");
+        let old = "# Order\n\n## Aggregates\n\nOrder\n";
+        let new = "# Order\n\n## Aggregates\n\nOrder\nInvoice\n";
+
+        assert_eq!(
+            render_diff(&from, &to, old, new, false),
+            "--- 0.1.0\n+++ 0.2.0\n@@ -3,3 +3,4 @@\n ## Aggregates\n \n Order\n+Invoice\n"
+        );
+
+        let colored = render_diff(&from, &to, old, new, true);
+        assert!(colored.contains("\x1b[32m+Invoice\x1b[0m\n"));
+        assert!(colored.contains(" Order\n"));
+        assert!(render_diff(&from, &to, old, old, true).is_empty());
+    }
+
+    #[test]
+    fn test_define_output_prints_created_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let businesses_dir = temp_dir.path().to_path_buf();
+        let app = BusinessApp::new(
+            BusinessProcessorAdapter::new(businesses_dir.clone(), DocumentProcessorAdapter::new()),
+            RegistryManager::new(MemoryProcessor::new(), MemoryDir::new(&businesses_dir)),
+        )
+        .with_silent_duplicate(true);
+
+        let definition = Definition::from("order");
+        let define = || {
+            app.define(
+                definition.clone(),
+                Some(FileVersion::from("1.0.0")),
+                FileLocation::default(),
+                "",
+            )
+            .unwrap()
+        };
+        let file_path = businesses_dir.join("order").join("1.0.0.md");
+        let current_dir = Path::new("/elsewhere");
+
+        let report = define();
+        assert_eq!(
+            define_output(&definition, &report, None, false, Some(current_dir)),
+            vec![
+                "added order 1.0.0".to_string(),
+                file_path.display().to_string()
+            ]
+        );
+        assert!(file_path.is_file());
+
+        let report = define();
+        assert_eq!(
+            define_output(&definition, &report, None, false, None),
+            vec!["order 1.0.0 already existed".to_string()]
+        );
+        assert_eq!(
+            define_output(&definition, &report, Some("sales"), false, None),
+            vec!["order 1.0.0 already existed, moved to domain sales".to_string()]
+        );
+        assert_eq!(
+            define_output(&definition, &report, None, true, Some(current_dir)),
+            vec![file_path.display().to_string()]
+        );
+        assert_eq!(
+            define_output(&definition, &report, None, true, None),
+            vec!["1.0.0".to_string()]
+        );
+
+        let relative = DefineReport {
+            path: PathBuf::from("businesses/order/1.0.0.md"),
+            ..report
+        };
+        assert_eq!(
+            define_output(&definition, &relative, None, true, Some(current_dir)),
+            vec!["/elsewhere/businesses/order/1.0.0.md".to_string()]
+        );
+    }
+
+    mod test_define_parameters {
+        use super::*;
+
+        fn build(name: &str, version: &str, language: &str, architect: &str) -> AnalyzeParameters {
+            define_parameters(
+                Definition::from(name),
+                FileVersion::from(version),
+                Some(language.to_string()),
+                Some(architect.to_string()),
+                Some(String::new()),
+                false,
+                false,
+            )
+        }
+
+        #[test]
+        fn test_valid_inputs() {
+            let params = build("order", "1.0.0", "Rust", "Modular Monolith");
+            assert!(params.additional_prompt.is_none());
+            assert!(validate(&params.with_strict(true)).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_inputs() {
+            let cases = [
+                (
+                    build("", "1.0.0", "Rust", "Modular Monolith"),
+                    "Definition cannot be empty",
+                ),
+                (
+                    build("order", "1.0", "Rust", "Modular Monolith"),
+                    "File version can have at most three parts",
+                ),
+                (
+                    build("order", "1.0.0", "", "Modular Monolith"),
+                    "Language cannot be empty",
+                ),
+                (
+                    build("order", "1.0.0", "Bash", "Microservices").with_strict(true),
+                    "unusual pairing",
+                ),
+            ];
+
+            for (params, message) in cases {
+                match validate(&params) {
+                    Err(CoreError::ValidationError { message: msg, .. }) => {
+                        assert!(msg.contains(message))
+                    }
+                    _ => panic!("Expected ValidationError containing {:?}", message),
+                }
+            }
         }
     }
 }
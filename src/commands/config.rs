@@ -0,0 +1,341 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use crate::core::project::types::{
+    ProjectError, RegistryConfig, PROJECT_BUSINESS_DIR_NAME, PROJECT_CREDENTIAL_NAME,
+    PROJECT_DIR_NAME,
+};
+use crate::core::types::ToJSON;
+
+use crate::cli::{LogFormat, OutputFormat};
+use crate::commands::adapters::document::processor::ProcessorAdapter as DocumentProcessorAdapter;
+use crate::commands::business::{DEFAULT_ARCHITECTURE, DEFAULT_LANGUAGE};
+use crate::commands::project::load_project;
+
+/// Printed instead of any secret value.
+const MASKED_VALUE: &str = "********";
+
+#[derive(Args)]
+pub(crate) struct ConfigArgs {
+    #[command(subcommand)]
+    pub commands: Config,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Config {
+    /// Print the effective configuration and where each value comes from, secrets are masked
+    Dump {
+        /// Resolve the businesses directory as `business --businesses-dir` would
+        #[arg(long)]
+        businesses_dir: Option<PathBuf>,
+
+        /// Resolve the registry directory as `business --registry-dir` would
+        #[arg(long)]
+        registry_dir: Option<PathBuf>,
+    },
+}
+
+/// `ConfigSource` tells where an effective configuration value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ConfigSource {
+    Default,
+    Env,
+    Flag,
+    Project,
+}
+
+impl ConfigSource {
+    /// Where clap took the value of the given argument from.
+    pub(crate) fn of(matches: &ArgMatches, id: &str) -> Self {
+        match matches.value_source(id) {
+            Some(ValueSource::CommandLine) => ConfigSource::Flag,
+            Some(ValueSource::EnvVariable) => ConfigSource::Env,
+            _ => ConfigSource::Default,
+        }
+    }
+
+    fn or_flag<T>(flag: &Option<T>, otherwise: ConfigSource) -> Self {
+        match flag {
+            Some(_) => ConfigSource::Flag,
+            None => otherwise,
+        }
+    }
+
+    fn or_project<T>(value: &Option<T>) -> Self {
+        match value {
+            Some(_) => ConfigSource::Project,
+            None => ConfigSource::Default,
+        }
+    }
+}
+
+/// `ConfigEntry` is a single effective configuration value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct ConfigEntry {
+    key: &'static str,
+    value: String,
+    source: ConfigSource,
+}
+
+impl ConfigEntry {
+    fn new(key: &'static str, value: impl ToString, source: ConfigSource) -> Self {
+        ConfigEntry {
+            key,
+            value: value.to_string(),
+            source,
+        }
+    }
+}
+
+/// `EffectiveConfig` is the configuration a command would run with, in precedence order
+/// flag, env var, project file, then the built-in default.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct EffectiveConfig {
+    entries: Vec<ConfigEntry>,
+}
+
+impl ToJSON for EffectiveConfig {}
+
+impl EffectiveConfig {
+    pub(crate) fn resolve(
+        current_dir: &Path,
+        businesses_dir: Option<PathBuf>,
+        registry_dir: Option<PathBuf>,
+        log_format: (LogFormat, ConfigSource),
+    ) -> Result<Self, ProjectError> {
+        let project_dir = current_dir.join(PROJECT_DIR_NAME);
+        let project = load_project(&DocumentProcessorAdapter::new(), current_dir)?;
+        let registry = project
+            .as_ref()
+            .and_then(|project| project.registry.clone())
+            .unwrap_or_default();
+
+        let businesses_source = ConfigSource::or_flag(&businesses_dir, ConfigSource::Default);
+        let businesses_dir = current_dir
+            .join(businesses_dir.unwrap_or_else(|| PathBuf::from(PROJECT_BUSINESS_DIR_NAME)));
+        let registry_source = ConfigSource::or_flag(&registry_dir, businesses_source);
+        let registry_dir = registry_dir
+            .map(|dir| current_dir.join(dir))
+            .unwrap_or_else(|| businesses_dir.clone());
+
+        let RegistryConfig {
+            max_files,
+            max_versions,
+            format,
+            case_sensitive,
+            on_change,
+        } = registry;
+
+        let credentials = project_dir.join(PROJECT_CREDENTIAL_NAME);
+        let credentials = match credentials.is_file() {
+            true => ConfigEntry::new("credentials", MASKED_VALUE, ConfigSource::Project),
+            false => ConfigEntry::new("credentials", "none", ConfigSource::Default),
+        };
+
+        let (log_format, log_format_source) = log_format;
+        let log_format = log_format
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+
+        let entries = vec![
+            ConfigEntry::new(
+                "project_dir",
+                project_dir.display(),
+                ConfigSource::or_project(&project),
+            ),
+            ConfigEntry::new(
+                "businesses_dir",
+                businesses_dir.display(),
+                businesses_source,
+            ),
+            ConfigEntry::new("registry_dir", registry_dir.display(), registry_source),
+            ConfigEntry::new(
+                "registry.format",
+                format.unwrap_or_default().extension(),
+                ConfigSource::or_project(&format),
+            ),
+            ConfigEntry::new(
+                "registry.max_files",
+                max_files.map_or("unlimited".to_string(), |max| max.to_string()),
+                ConfigSource::or_project(&max_files),
+            ),
+            ConfigEntry::new(
+                "registry.max_versions",
+                max_versions.map_or("unlimited".to_string(), |max| max.to_string()),
+                ConfigSource::or_project(&max_versions),
+            ),
+            ConfigEntry::new(
+                "registry.case_sensitive",
+                case_sensitive.unwrap_or_default(),
+                ConfigSource::or_project(&case_sensitive),
+            ),
+            ConfigEntry::new(
+                "registry.on_change",
+                on_change.clone().unwrap_or("none".to_string()),
+                ConfigSource::or_project(&on_change),
+            ),
+            ConfigEntry::new("language", DEFAULT_LANGUAGE, ConfigSource::Default),
+            ConfigEntry::new("architecture", DEFAULT_ARCHITECTURE, ConfigSource::Default),
+            ConfigEntry::new("provider", "none", ConfigSource::Default),
+            credentials,
+            ConfigEntry::new("log_format", log_format, log_format_source),
+        ];
+
+        Ok(EffectiveConfig { entries })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Handler {
+    log_format: (LogFormat, ConfigSource),
+}
+
+impl Handler {
+    pub(crate) fn new(log_format: LogFormat, log_format_source: ConfigSource) -> Self {
+        Handler {
+            log_format: (log_format, log_format_source),
+        }
+    }
+
+    pub(crate) fn handle(
+        &self,
+        args: ConfigArgs,
+        output: OutputFormat,
+    ) -> Result<(), ProjectError> {
+        match args.commands {
+            Config::Dump {
+                businesses_dir,
+                registry_dir,
+            } => self.dump(businesses_dir, registry_dir, output),
+        }
+    }
+
+    fn dump(
+        &self,
+        businesses_dir: Option<PathBuf>,
+        registry_dir: Option<PathBuf>,
+        output: OutputFormat,
+    ) -> Result<(), ProjectError> {
+        let current_dir = env::current_dir().map_err(ProjectError::FsError)?;
+        let config =
+            EffectiveConfig::resolve(&current_dir, businesses_dir, registry_dir, self.log_format)?;
+
+        match output {
+            OutputFormat::Text => {
+                for entry in &config.entries {
+                    println!(
+                        "{}: {} ({})",
+                        entry.key,
+                        entry.value,
+                        source_name(entry.source)
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let json = config.to_json().map_err(ProjectError::ValidationError)?;
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn source_name(source: ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::Default => "default",
+        ConfigSource::Env => "env",
+        ConfigSource::Flag => "flag",
+        ConfigSource::Project => "project",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    fn entry<'a>(config: &'a EffectiveConfig, key: &str) -> &'a ConfigEntry {
+        config
+            .entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_defaults() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = EffectiveConfig::resolve(
+            temp_dir.path(),
+            None,
+            None,
+            (LogFormat::Pretty, ConfigSource::Default),
+        )
+        .unwrap();
+
+        let businesses_dir = entry(&config, "businesses_dir");
+        assert_eq!(businesses_dir.source, ConfigSource::Default);
+        assert_eq!(
+            businesses_dir.value,
+            temp_dir.path().join("businesses").display().to_string()
+        );
+        assert_eq!(entry(&config, "registry_dir").value, businesses_dir.value);
+        assert_eq!(entry(&config, "registry.format").value, "json");
+        assert_eq!(entry(&config, "credentials").value, "none");
+        assert_eq!(entry(&config, "log_format").value, "pretty");
+    }
+
+    #[test]
+    fn test_resolve_sources() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_dir = temp_dir.path().join(PROJECT_DIR_NAME);
+        fs::create_dir(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("project.json"),
+            r#"{
+                "name": "Sample Project",
+                "created_at": "2025-01-01T00:00:00Z",
+                "registry": { "format": "yaml" }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join(PROJECT_CREDENTIAL_NAME),
+            r#"{"api_key":"sk-secret"}"#,
+        )
+        .unwrap();
+
+        let config = EffectiveConfig::resolve(
+            temp_dir.path(),
+            Some(PathBuf::from("domain")),
+            None,
+            (LogFormat::Json, ConfigSource::Env),
+        )
+        .unwrap();
+
+        assert_eq!(entry(&config, "project_dir").source, ConfigSource::Project);
+        assert_eq!(entry(&config, "businesses_dir").source, ConfigSource::Flag);
+        assert_eq!(entry(&config, "registry_dir").source, ConfigSource::Flag);
+
+        let format = entry(&config, "registry.format");
+        assert_eq!(format.value, "yaml");
+        assert_eq!(format.source, ConfigSource::Project);
+        assert_eq!(
+            entry(&config, "registry.max_files").source,
+            ConfigSource::Default
+        );
+        assert_eq!(entry(&config, "log_format").source, ConfigSource::Env);
+
+        let json = config.to_json().unwrap();
+        assert!(json.contains(MASKED_VALUE));
+        assert!(!json.contains("sk-secret"));
+    }
+}
@@ -0,0 +1,189 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum EnvFileError {
+    #[error("[env file] unable to read {0}: {1}")]
+    ReadError(String, io::Error),
+
+    #[error("[env file] invalid line {0}: {1}")]
+    ParseError(usize, String),
+}
+
+/// `parse` reads the `KEY=value` pairs of a `.env` style file, in order.
+///
+/// Blank lines and `#` comments are skipped, and so is an `export ` prefix. A value may be
+/// single-quoted, taken literally, or double-quoted, where `\n`, `\"` and `\\` are
+/// unescaped. An unquoted value ends at a ` #` comment and is trimmed.
+pub(crate) fn parse(content: &str) -> Result<Vec<(String, String)>, EnvFileError> {
+    let mut pairs = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            EnvFileError::ParseError(line_number, "expected KEY=value".to_string())
+        })?;
+
+        let key = key.trim();
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(EnvFileError::ParseError(
+                line_number,
+                format!("invalid variable name `{}`", key),
+            ));
+        }
+
+        let value = parse_value(value.trim())
+            .map_err(|message| EnvFileError::ParseError(line_number, message))?;
+        pairs.push((key.to_string(), value));
+    }
+
+    Ok(pairs)
+}
+
+fn parse_value(value: &str) -> Result<String, String> {
+    let quote = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => {
+            let value = match value.find(" #") {
+                Some(comment) => &value[..comment],
+                None => value,
+            };
+            return Ok(value.trim().to_string());
+        }
+    };
+
+    let mut parsed = String::new();
+    let mut chars = value[1..].chars();
+    loop {
+        match chars.next() {
+            None => return Err(format!("unterminated {} quote", quote)),
+            Some(c) if c == quote => break,
+            Some('\\') if quote == '"' => match chars.next() {
+                Some('n') => parsed.push('\n'),
+                Some(c @ ('"' | '\\')) => parsed.push(c),
+                Some(c) => {
+                    parsed.push('\\');
+                    parsed.push(c);
+                }
+                None => return Err(format!("unterminated {} quote", quote)),
+            },
+            Some(c) => parsed.push(c),
+        }
+    }
+
+    let rest = chars.as_str().trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected `{}` after the quoted value", rest));
+    }
+
+    Ok(parsed)
+}
+
+/// `load` sets the variables of an env file in the process environment, and returns the
+/// names of those it set.
+///
+/// A variable which is already set keeps its value unless `override_env` is given. It must
+/// run before any other thread is spawned, since the environment is shared by the process.
+pub(crate) fn load(path: &Path, override_env: bool) -> Result<Vec<String>, EnvFileError> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| EnvFileError::ReadError(path.display().to_string(), err))?;
+
+    let mut loaded = vec![];
+    for (key, value) in parse(&content)? {
+        if override_env || env::var_os(&key).is_none() {
+            env::set_var(&key, value);
+            loaded.push(key);
+        }
+    }
+
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = r#"
+# credentials for local development
+OPENAI_API_KEY=sk-local # not a real key
+export DDAI_LOG_FORMAT = json
+EMPTY=
+GREETING="hello \"world\"\nbye" # trailing comment
+LITERAL='no \n escape # here'
+URL=http://localhost:8080/#anchor
+"#;
+
+        let pairs = parse(content).unwrap();
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("OPENAI_API_KEY", "sk-local"),
+                ("DDAI_LOG_FORMAT", "json"),
+                ("EMPTY", ""),
+                ("GREETING", "hello \"world\"\nbye"),
+                ("LITERAL", "no \\n escape # here"),
+                ("URL", "http://localhost:8080/#anchor"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (content, line) in [
+            ("A=1\nNOT A PAIR", 2),
+            ("1KEY=value", 1),
+            ("KEY=\"unterminated", 1),
+            ("KEY='quoted' extra", 1),
+        ] {
+            match parse(content) {
+                Err(EnvFileError::ParseError(at, _)) => assert_eq!(at, line, "{}", content),
+                _ => panic!("Expected ParseError for {}", content),
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_keeps_set_variables() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let env_file = temp_dir.path().join(".env");
+        fs::write(
+            &env_file,
+            "DDAI_TEST_ENV_FILE_SET=from-file\nDDAI_TEST_ENV_FILE_UNSET=from-file\n",
+        )
+        .unwrap();
+        env::set_var("DDAI_TEST_ENV_FILE_SET", "from-env");
+
+        let loaded = load(&env_file, false).unwrap();
+        assert_eq!(loaded, vec!["DDAI_TEST_ENV_FILE_UNSET".to_string()]);
+        assert_eq!(env::var("DDAI_TEST_ENV_FILE_SET").unwrap(), "from-env");
+        assert_eq!(env::var("DDAI_TEST_ENV_FILE_UNSET").unwrap(), "from-file");
+
+        let loaded = load(&env_file, true).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(env::var("DDAI_TEST_ENV_FILE_SET").unwrap(), "from-file");
+
+        assert!(matches!(
+            load(&temp_dir.path().join("missing.env"), false),
+            Err(EnvFileError::ReadError(..))
+        ));
+    }
+}
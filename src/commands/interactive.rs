@@ -0,0 +1,277 @@
+use crate::core::business::types::{Architecture, BusinessError, Definition, Language};
+use crate::core::registry::types::{FileVersion, REGISTRY_VERSION_GENESIS};
+use crate::core::types::Validator;
+
+use crate::commands::business::{Increment, DEFAULT_ARCHITECTURE, DEFAULT_LANGUAGE};
+
+/// The version strategies offered by `business define --interactive`, in display order.
+const VERSION_STRATEGIES: [&str; 5] = [
+    "initial version",
+    "next patch version",
+    "next minor version",
+    "next major version",
+    "explicit version",
+];
+
+/// `Prompt` asks the user for the missing inputs of a command.
+///
+/// `input` keeps asking until `check` accepts the answer, showing its error in between.
+pub(crate) trait Prompt {
+    fn input(
+        &self,
+        prompt: &str,
+        default: Option<&str>,
+        check: &dyn Fn(&str) -> Result<(), String>,
+    ) -> Result<String, BusinessError>;
+
+    fn select(&self, prompt: &str, items: &[&str]) -> Result<usize, BusinessError>;
+
+    fn confirm(&self, prompt: &str) -> Result<bool, BusinessError>;
+}
+
+/// `DefineAnswers` holds the `business define` inputs which can be prompted for, a value
+/// given as a flag is never asked again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DefineAnswers {
+    pub(crate) business_name: Option<String>,
+    pub(crate) business_version: Option<String>,
+    pub(crate) increment: Option<Increment>,
+    pub(crate) language: Option<String>,
+    pub(crate) architect: Option<String>,
+}
+
+/// `ask_define` prompts for every missing define input, each answer being validated before
+/// moving on to the next one.
+pub(crate) fn ask_define(
+    prompt: &impl Prompt,
+    answers: DefineAnswers,
+) -> Result<DefineAnswers, BusinessError> {
+    let mut answers = answers;
+
+    if answers.business_name.is_none() {
+        let name = prompt.input("Business name", None, &|input| {
+            check(&Definition::from(input))
+        })?;
+        answers.business_name = Some(name);
+    }
+
+    if answers.business_version.is_none() && answers.increment.is_none() {
+        match prompt.select("Version", &VERSION_STRATEGIES)? {
+            0 => answers.business_version = Some(REGISTRY_VERSION_GENESIS.to_string()),
+            1 => answers.increment = Some(Increment::Patch),
+            2 => answers.increment = Some(Increment::Minor),
+            3 => answers.increment = Some(Increment::Major),
+            _ => {
+                let version = prompt.input("Explicit version", None, &|input| {
+                    check(&FileVersion::from(input))
+                })?;
+                answers.business_version = Some(version);
+            }
+        }
+    }
+
+    if answers.language.is_none() {
+        let language = prompt.input("Language", Some(DEFAULT_LANGUAGE), &|input| {
+            check(&Language::from(input))
+        })?;
+        answers.language = Some(language);
+    }
+
+    if answers.architect.is_none() {
+        let architect = prompt.input("Architecture", Some(DEFAULT_ARCHITECTURE), &|input| {
+            check(&Architecture::from(input))
+        })?;
+        answers.architect = Some(architect);
+    }
+
+    Ok(answers)
+}
+
+fn check(value: &dyn Validator) -> Result<(), String> {
+    value.validate().map_err(|err| err.to_string())
+}
+
+/// `terminal` returns the prompt reading from the terminal, available with the
+/// `interactive` feature only.
+#[cfg(feature = "interactive")]
+pub(crate) fn terminal() -> Result<impl Prompt, BusinessError> {
+    Ok(TerminalPrompt)
+}
+
+#[cfg(not(feature = "interactive"))]
+pub(crate) fn terminal() -> Result<impl Prompt, BusinessError> {
+    Err::<NoPrompt, _>(BusinessError::PromptFailed(
+        "ddai was built without the `interactive` feature".to_string(),
+    ))
+}
+
+/// `TerminalPrompt` asks on the terminal, prompts are written to stderr so stdout only
+/// carries the command result.
+#[cfg(feature = "interactive")]
+#[derive(Debug, Clone, Copy)]
+struct TerminalPrompt;
+
+#[cfg(feature = "interactive")]
+impl Prompt for TerminalPrompt {
+    fn input(
+        &self,
+        prompt: &str,
+        default: Option<&str>,
+        check: &dyn Fn(&str) -> Result<(), String>,
+    ) -> Result<String, BusinessError> {
+        let input = dialoguer::Input::<String>::new()
+            .with_prompt(prompt)
+            .validate_with(|input: &String| check(input.trim()));
+        let input = match default {
+            Some(default) => input.default(default.to_string()),
+            None => input,
+        };
+
+        input
+            .interact_text()
+            .map(|answer| answer.trim().to_string())
+            .map_err(|err| BusinessError::PromptFailed(err.to_string()))
+    }
+
+    fn select(&self, prompt: &str, items: &[&str]) -> Result<usize, BusinessError> {
+        dialoguer::Select::new()
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .interact()
+            .map_err(|err| BusinessError::PromptFailed(err.to_string()))
+    }
+
+    fn confirm(&self, prompt: &str) -> Result<bool, BusinessError> {
+        dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(true)
+            .interact()
+            .map_err(|err| BusinessError::PromptFailed(err.to_string()))
+    }
+}
+
+/// `NoPrompt` stands for the absence of a terminal prompt.
+///
+/// It has no values, so it can never be asked anything.
+#[cfg(not(feature = "interactive"))]
+#[derive(Debug, Clone)]
+enum NoPrompt {}
+
+#[cfg(not(feature = "interactive"))]
+impl Prompt for NoPrompt {
+    fn input(
+        &self,
+        _prompt: &str,
+        _default: Option<&str>,
+        _check: &dyn Fn(&str) -> Result<(), String>,
+    ) -> Result<String, BusinessError> {
+        match *self {}
+    }
+
+    fn select(&self, _prompt: &str, _items: &[&str]) -> Result<usize, BusinessError> {
+        match *self {}
+    }
+
+    fn confirm(&self, _prompt: &str) -> Result<bool, BusinessError> {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// Answers the prompts from a script, recording every prompt and rejected answer.
+    struct ScriptedPrompt {
+        answers: RefCell<VecDeque<&'static str>>,
+        asked: RefCell<Vec<String>>,
+    }
+
+    impl ScriptedPrompt {
+        fn new(answers: &[&'static str]) -> Self {
+            ScriptedPrompt {
+                answers: RefCell::new(answers.iter().copied().collect()),
+                asked: RefCell::new(vec![]),
+            }
+        }
+
+        fn next(&self, prompt: &str) -> &'static str {
+            self.asked.borrow_mut().push(prompt.to_string());
+            self.answers.borrow_mut().pop_front().unwrap()
+        }
+    }
+
+    impl Prompt for ScriptedPrompt {
+        fn input(
+            &self,
+            prompt: &str,
+            default: Option<&str>,
+            check: &dyn Fn(&str) -> Result<(), String>,
+        ) -> Result<String, BusinessError> {
+            loop {
+                let answer = match self.next(prompt) {
+                    "" => default.unwrap_or_default(),
+                    answer => answer,
+                };
+                match check(answer) {
+                    Ok(()) => return Ok(answer.to_string()),
+                    Err(err) => self.asked.borrow_mut().push(err),
+                }
+            }
+        }
+
+        fn select(&self, prompt: &str, _items: &[&str]) -> Result<usize, BusinessError> {
+            Ok(self.next(prompt).parse().unwrap())
+        }
+
+        fn confirm(&self, prompt: &str) -> Result<bool, BusinessError> {
+            Ok(self.next(prompt) == "y")
+        }
+    }
+
+    #[test]
+    fn test_ask_every_missing_answer() {
+        let prompt = ScriptedPrompt::new(&["", "order", "4", "one", "1.2.0", "", "Hexagonal"]);
+        let answers = ask_define(&prompt, DefineAnswers::default()).unwrap();
+
+        assert_eq!(
+            answers,
+            DefineAnswers {
+                business_name: Some("order".to_string()),
+                business_version: Some("1.2.0".to_string()),
+                increment: None,
+                language: Some(DEFAULT_LANGUAGE.to_string()),
+                architect: Some("Hexagonal".to_string()),
+            }
+        );
+
+        // the empty name and the invalid version were asked again
+        let asked = prompt.asked.borrow();
+        assert_eq!(asked.iter().filter(|p| *p == "Business name").count(), 2);
+        assert_eq!(asked.iter().filter(|p| *p == "Explicit version").count(), 2);
+        assert_eq!(asked.len(), 9);
+    }
+
+    #[test]
+    fn test_skip_answers_given_as_flags() {
+        let prompt = ScriptedPrompt::new(&["2"]);
+        let answers = ask_define(
+            &prompt,
+            DefineAnswers {
+                business_name: Some("order".to_string()),
+                language: Some("Go".to_string()),
+                architect: Some("Microservices".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(answers.increment, Some(Increment::Minor));
+        assert_eq!(answers.language.as_deref(), Some("Go"));
+        assert_eq!(*prompt.asked.borrow(), vec!["Version".to_string()]);
+    }
+}
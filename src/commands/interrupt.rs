@@ -0,0 +1,194 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing::warn;
+
+/// Exit code conventionally used by shells for a process terminated by SIGINT.
+pub(crate) const INTERRUPT_EXIT_CODE: u8 = 130;
+
+static IN_CRITICAL: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// `install` registers the SIGINT handler.
+///
+/// Outside of a [`critical`] section an interrupt terminates the process right away.
+/// Inside one, the interrupt is deferred until the section completes, so a write sequence
+/// is never cut in the middle.
+#[cfg(feature = "interrupt")]
+pub(crate) fn install() -> Result<(), ctrlc::Error> {
+    ctrlc::set_handler(|| {
+        if !on_interrupt() {
+            std::process::exit(i32::from(INTERRUPT_EXIT_CODE));
+        }
+    })
+}
+
+/// Records an interrupt, returns `true` when it has been deferred by a critical section.
+#[cfg_attr(not(feature = "interrupt"), allow(dead_code))]
+fn on_interrupt() -> bool {
+    if !IN_CRITICAL.load(Ordering::SeqCst) {
+        return false;
+    }
+
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    warn!("Interrupt received, finishing the current write before exiting");
+    true
+}
+
+/// `critical` runs a write sequence that must not be cut by an interrupt.
+///
+/// The section always runs to its end. Callers check [`interrupted`] right after it and
+/// stop there, the process then exits with [`INTERRUPT_EXIT_CODE`].
+pub(crate) fn critical<T>(f: impl FnOnce() -> T) -> T {
+    IN_CRITICAL.store(true, Ordering::SeqCst);
+    let out = f();
+    IN_CRITICAL.store(false, Ordering::SeqCst);
+
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        warn!("Write sequence completed after an interrupt, stopping");
+    }
+
+    out
+}
+
+/// `interrupted` tells whether an interrupt was deferred by a critical section.
+pub(crate) fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    use crate::commands::adapters::business::processor::ProcessorAdapter as BusinessProcessorAdapter;
+    use crate::commands::adapters::document::processor::ProcessorAdapter as DocumentProcessorAdapter;
+    use crate::core::business::app::App;
+    use crate::core::business::types::{BusinessError, Definition, DefinitionFile, Processor};
+    use crate::core::registry::manager::Manager as RegistryManager;
+    use crate::core::registry::memory::{MemoryDir, MemoryProcessor};
+    use crate::core::registry::types::{FileLocation, FileName, FileVersion};
+
+    /// The interrupt state is process wide, the tests touching it run one at a time.
+    static STATE: Mutex<()> = Mutex::new(());
+
+    fn reset() {
+        IN_CRITICAL.store(false, Ordering::SeqCst);
+        INTERRUPTED.store(false, Ordering::SeqCst);
+    }
+
+    /// A business processor receiving an interrupt right after writing the definition file,
+    /// the critical point where the registry isn't updated yet.
+    struct InterruptingProcessor<P: Processor>(P);
+
+    impl<P: Processor> Processor for InterruptingProcessor<P> {
+        fn define(
+            &self,
+            definition: Definition,
+            version: FileVersion,
+            location: FileLocation,
+            content: &str,
+        ) -> Result<PathBuf, BusinessError> {
+            let path = self.0.define(definition, version, location, content)?;
+            assert!(on_interrupt(), "inside a critical section it must defer");
+            Ok(path)
+        }
+
+        fn exists(
+            &self,
+            definition: &Definition,
+            version: &FileVersion,
+            location: &FileLocation,
+        ) -> bool {
+            self.0.exists(definition, version, location)
+        }
+
+        fn path(
+            &self,
+            definition: &Definition,
+            version: &FileVersion,
+            location: &FileLocation,
+        ) -> PathBuf {
+            self.0.path(definition, version, location)
+        }
+
+        fn export(
+            &self,
+            definition: Definition,
+            version: FileVersion,
+            location: FileLocation,
+            target_dir: PathBuf,
+        ) -> Result<(), BusinessError> {
+            self.0.export(definition, version, location, target_dir)
+        }
+
+        fn scan(&self) -> Result<Vec<DefinitionFile>, BusinessError> {
+            self.0.scan()
+        }
+
+        fn read(
+            &self,
+            definition: Definition,
+            version: FileVersion,
+            location: FileLocation,
+        ) -> Result<String, BusinessError> {
+            self.0.read(definition, version, location)
+        }
+    }
+
+    #[test]
+    fn test_interrupt_outside_critical_section_is_not_deferred() {
+        let _state = STATE.lock().unwrap();
+        reset();
+
+        assert!(!on_interrupt());
+        assert!(!interrupted());
+    }
+
+    #[test]
+    fn test_interrupt_during_define_keeps_file_and_registry_consistent() {
+        let _state = STATE.lock().unwrap();
+        reset();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let businesses_dir = temp_dir.path().to_path_buf();
+        let registry = MemoryProcessor::new();
+        let app = App::new(
+            InterruptingProcessor(BusinessProcessorAdapter::new(
+                businesses_dir.clone(),
+                DocumentProcessorAdapter::new(),
+            )),
+            RegistryManager::new(registry.clone(), MemoryDir::new(&businesses_dir)),
+        );
+
+        let definition = Definition::from("order");
+        let version = FileVersion::from("1.0.0");
+        let report = critical(|| {
+            app.define(
+                definition.clone(),
+                Some(version.clone()),
+                FileLocation::default(),
+                "# Order",
+            )
+        })
+        .unwrap();
+
+        assert!(interrupted());
+        assert!(report.added);
+        assert_eq!(report.version, version);
+        assert_eq!(report.path, businesses_dir.join("order").join("1.0.0.md"));
+        assert_eq!(std::fs::read_to_string(&report.path).unwrap(), "# Order");
+        let registry = registry
+            .registry(businesses_dir.join("registry.json"))
+            .unwrap();
+        assert_eq!(
+            registry
+                .get_file(&FileName::from("order"))
+                .unwrap()
+                .versions,
+            vec![version]
+        );
+        reset();
+    }
+}
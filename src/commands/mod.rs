@@ -1,3 +1,8 @@
 pub mod adapters;
 pub mod business;
+pub(crate) mod config;
+pub(crate) mod env_file;
+pub(crate) mod interactive;
+pub(crate) mod interrupt;
 pub mod project;
+pub mod version;
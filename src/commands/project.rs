@@ -1,17 +1,23 @@
 use std::env;
-use std::fs::{create_dir, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::fs::{self, read_dir};
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use clap::{Args, Subcommand};
+use serde::Serialize;
 use tracing::{debug, error, info, instrument};
 
+use crate::commands::adapters::document::processor::ProcessorAdapter as DocumentProcessorAdapter;
+use crate::commands::adapters::path_buf_wrapper::PathBufAdapter;
+use crate::core::document::types::{FilePath, FsProcessor};
 use crate::core::project::app::App as ProjectApp;
 use crate::core::project::types::{
-    Builder, Project as CoreProject, ProjectError, PROJECT_ARCHITECTURE_DIR_NAME,
+    Builder, InitiatedPath, Project as CoreProject, ProjectError, PROJECT_ARCHITECTURE_DIR_NAME,
     PROJECT_BUSINESS_DIR_NAME, PROJECT_CREDENTIAL_NAME, PROJECT_DIR_NAME, PROJECT_FILE_NAME,
 };
-use crate::core::types::ToJSON;
+use crate::core::types::{PathBufWrapper, ToJSON};
+
+use crate::cli::OutputFormat;
 
 #[derive(Args)]
 pub(crate) struct ProjectArgs {
@@ -19,137 +25,939 @@ pub(crate) struct ProjectArgs {
     pub commands: Project,
 }
 
+impl ProjectArgs {
+    pub(crate) fn quiet(&self) -> bool {
+        match &self.commands {
+            Project::Init { quiet, .. } => *quiet,
+            Project::Status | Project::Clean { .. } => false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub(crate) enum Project {
     /// Create a new project
     Init {
-        /// The name of the project to initiate
-        #[arg(long, required = true)]
-        name: String,
+        /// The name of the project to initiate, defaults to the package name of the
+        /// `Cargo.toml` or `package.json` found in the current directory
+        #[arg(long)]
+        name: Option<String>,
 
-        /// The description of the project
+        /// The description of the project, defaults to the detected package description
+        /// when the name is detected as well
         #[arg(long)]
         desc: Option<String>,
+
+        /// Who creates the project, defaults to the `USER` or `USERNAME` environment variable
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Overwrite the project file of an already initialized project
+        #[arg(long)]
+        force: bool,
+
+        /// Suppress the informational logs, errors are still reported
+        #[arg(long)]
+        quiet: bool,
+
+        /// A directory whose content is copied into the new project, `{{project_name}}` and
+        /// `{{description}}` are substituted in its text files
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+    },
+
+    /// Show the project of the current directory
+    Status,
+
+    /// Remove the generated architecture outputs and `.raw`/`.prompt` files, business
+    /// definitions and the registry are kept
+    Clean {
+        /// List what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
+/// `InitSummary` is the machine-readable report printed by `project init --output json`.
+#[derive(Debug, Serialize)]
+struct InitSummary {
+    name: String,
+    created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+
+    paths: Vec<InitiatedPath>,
+}
+
+impl ToJSON for InitSummary {}
+
+/// `CleanSummary` is the machine-readable report printed by `project clean --output json`.
+#[derive(Debug, Serialize)]
+struct CleanSummary {
+    dry_run: bool,
+    paths: Vec<PathBuf>,
+}
+
+impl ToJSON for CleanSummary {}
+
+/// Extensions of the generated files which may sit next to the business definitions.
+const GENERATED_EXTENSIONS: [&str; 2] = ["raw", "prompt"];
+
+/// Placeholder substituted with the project name in template text files.
+const TEMPLATE_PROJECT_NAME: &str = "{{project_name}}";
+
+/// Placeholder substituted with the project description in template text files.
+const TEMPLATE_DESCRIPTION: &str = "{{description}}";
+
+/// `ProjectBuilderImpl` lays out a new project in its root directory, every write goes
+/// through the document processor. The template directory is only read from disk.
 #[derive(Debug, Clone)]
-struct ProjectBuilderImpl;
+struct ProjectBuilderImpl<T: PathBufWrapper, D: FsProcessor> {
+    root: T,
+    document: D,
+    template_dir: Option<PathBuf>,
+}
+
+impl<T, D> ProjectBuilderImpl<T, D>
+where
+    T: PathBufWrapper,
+    D: FsProcessor,
+{
+    fn new(root: T, document: D) -> Self {
+        ProjectBuilderImpl {
+            root,
+            document,
+            template_dir: None,
+        }
+    }
+
+    fn with_template_dir(mut self, template_dir: Option<PathBuf>) -> Self {
+        self.template_dir = template_dir;
+        self
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.document.exists(FilePath::from(path.to_path_buf()))
+    }
+
+    /// Creates a directory unless it already exists, telling whether it was created.
+    fn create_dir(&self, path: &Path) -> Result<bool, ProjectError> {
+        let created = !self.exists(path);
+        if created {
+            self.document
+                .create_dir(FilePath::from(path.to_path_buf()))?;
+        }
+
+        Ok(created)
+    }
+
+    fn write(&self, path: &Path, content: &[u8]) -> Result<(), ProjectError> {
+        self.document
+            .write_all(FilePath::from(path.to_path_buf()), content)
+            .map_err(ProjectError::from)
+    }
 
-impl ProjectBuilderImpl {
     #[instrument(skip_all, err)]
-    fn create_project_dir(&self, current_dir: PathBuf) -> Result<(), ProjectError> {
+    fn create_project_dir(&self, current_dir: &Path) -> Result<InitiatedPath, ProjectError> {
         let project_dir = current_dir.join(PROJECT_DIR_NAME);
         debug!("Creating project directory at: {:?}", project_dir);
 
-        debug!(
-            "Checking if project directory exists: {:?}",
-            project_dir.exists()
-        );
-        if !project_dir.exists() {
-            let _ = create_dir(&project_dir).map_err(|e| ProjectError::FsError(e))?;
-        }
-
-        Ok(())
+        let created = self.create_dir(&project_dir)?;
+        Ok(InitiatedPath::new(project_dir, created))
     }
 
     #[instrument(skip_all, err)]
-    fn create_project_file(&self, current_dir: PathBuf, json: String) -> Result<(), ProjectError> {
-        let file_path = current_dir.join(format!("{}/{}", PROJECT_DIR_NAME, PROJECT_FILE_NAME));
+    fn create_project_file(
+        &self,
+        current_dir: &Path,
+        json: String,
+    ) -> Result<InitiatedPath, ProjectError> {
+        let file_path = current_dir.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
         debug!("Project file path: {:?}", file_path);
 
-        if !file_path.exists() {
-            let mut file = File::create(&file_path).map_err(|e| ProjectError::FsError(e))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| ProjectError::FsError(e))?;
-        }
+        // an existing project file only reaches here when overwriting was forced
+        let created = !self.exists(&file_path);
+        self.write(&file_path, json.as_bytes())?;
 
-        Ok(())
+        Ok(InitiatedPath::new(file_path, created))
     }
 
     #[instrument(skip_all, err)]
-    fn manage_gitignore(&self, current_dir: PathBuf) -> Result<(), ProjectError> {
+    fn manage_gitignore(&self, current_dir: &Path) -> Result<InitiatedPath, ProjectError> {
         let gitignore_path = current_dir.join(PROJECT_DIR_NAME).join(".gitignore");
         debug!("Creating .gitignore at: {:?}", gitignore_path);
 
-        debug!(
-            "Checking if .gitignore exists: {:?}",
-            gitignore_path.exists()
-        );
-        if !gitignore_path.exists() {
-            let mut file = File::create(gitignore_path).map_err(|e| ProjectError::FsError(e))?;
-            file.write_all(PROJECT_CREDENTIAL_NAME.as_bytes())
-                .map_err(|e| ProjectError::FsError(e))?;
+        let created = !self.exists(&gitignore_path);
+        if created {
+            self.write(&gitignore_path, PROJECT_CREDENTIAL_NAME.as_bytes())?;
         }
 
-        Ok(())
+        Ok(InitiatedPath::new(gitignore_path, created))
     }
 
     #[instrument(skip_all, err)]
-    fn create_business_dir(&self, current_dir: PathBuf) -> Result<(), ProjectError> {
+    fn create_business_dir(&self, current_dir: &Path) -> Result<InitiatedPath, ProjectError> {
         let business_dir = current_dir.join(PROJECT_BUSINESS_DIR_NAME);
         debug!("Creating business directory at: {:?}", business_dir);
 
-        if !business_dir.exists() {
-            let _ = create_dir(&business_dir).map_err(|e| ProjectError::FsError(e))?;
-        }
-
-        Ok(())
+        let created = self.create_dir(&business_dir)?;
+        Ok(InitiatedPath::new(business_dir, created))
     }
 
     #[instrument(skip_all, err)]
-    fn create_architecture_dir(&self, current_dir: PathBuf) -> Result<(), ProjectError> {
+    fn create_architecture_dir(&self, current_dir: &Path) -> Result<InitiatedPath, ProjectError> {
         let architecture_dir = current_dir.join(PROJECT_ARCHITECTURE_DIR_NAME);
         debug!("Creating architecture directory at: {:?}", architecture_dir);
 
-        if !architecture_dir.exists() {
-            let _ = create_dir(&architecture_dir).map_err(|e| ProjectError::FsError(e))?;
+        let created = self.create_dir(&architecture_dir)?;
+        Ok(InitiatedPath::new(architecture_dir, created))
+    }
+
+    /// Copies the template directory into the project, files already there are left untouched.
+    #[instrument(skip_all, err)]
+    fn copy_template(
+        &self,
+        template_dir: &Path,
+        target_dir: &Path,
+        project: &CoreProject,
+    ) -> Result<Vec<InitiatedPath>, ProjectError> {
+        let mut entries = read_dir(template_dir)
+            .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+            .map_err(ProjectError::FsError)?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut paths = vec![];
+        for entry in entries {
+            let source = entry.path();
+            let target = target_dir.join(entry.file_name());
+
+            if source.is_dir() {
+                let created = self.create_dir(&target)?;
+                paths.push(InitiatedPath::new(target.clone(), created));
+                paths.extend(self.copy_template(&source, &target, project)?);
+                continue;
+            }
+
+            let created = !self.exists(&target);
+            if created {
+                debug!("Copying template file {:?} to {:?}", source, target);
+                let content = fs::read(&source).map_err(ProjectError::FsError)?;
+                self.write(&target, &render_template(content, project))?;
+            }
+            paths.push(InitiatedPath::new(target, created));
         }
 
-        Ok(())
+        Ok(paths)
     }
 }
 
-impl Builder for ProjectBuilderImpl {
+impl<T, D> Builder for ProjectBuilderImpl<T, D>
+where
+    T: PathBufWrapper,
+    D: FsProcessor,
+{
     #[instrument(skip_all, err)]
-    fn initiate(&self, project: CoreProject) -> Result<(), ProjectError> {
+    fn initiate(
+        &self,
+        project: CoreProject,
+        force: bool,
+    ) -> Result<Vec<InitiatedPath>, ProjectError> {
         info!("Initiating project: {}", project.name.as_str());
-        let current_dir = env::current_dir().map_err(|e| ProjectError::FsError(e))?;
+        let current_dir = self.root.to_path_buf();
         debug!("Current directory: {:?}", current_dir);
 
+        if let Some(template_dir) = &self.template_dir {
+            if !template_dir.is_dir() {
+                return Err(ProjectError::InitiateError(format!(
+                    "template directory not found: {}",
+                    template_dir.display()
+                )));
+            }
+        }
+
+        let file_path = current_dir.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
+        if self.exists(&file_path) && !force {
+            return Err(ProjectError::AlreadyInitialized(
+                file_path.display().to_string(),
+            ));
+        }
+
+        // a forced init rewrites the existing project, which keeps its creation time and
+        // records the time of this init as its modification time
+        let mut project = project;
+        // an unreadable project file fails the init rather than losing its creation time
+        if let Some(existing) = load_project(&self.document, &current_dir)? {
+            let initiated_at = project.created_at;
+            project.created_at = existing.created_at;
+            project.touch(initiated_at);
+        }
+
         let json = project
             .to_json()
             .map_err(|e| ProjectError::InitiateError(e.to_string()))?;
 
-        let _ = self.create_project_dir(current_dir.clone())?;
-        let _ = self.create_project_file(current_dir.clone(), json)?;
-        let _ = self.manage_gitignore(current_dir.clone())?;
-        let _ = self.create_business_dir(current_dir.clone())?;
-        let _ = self.create_architecture_dir(current_dir)?;
+        let mut paths = vec![
+            self.create_project_dir(&current_dir)?,
+            self.create_project_file(&current_dir, json)?,
+            self.manage_gitignore(&current_dir)?,
+            self.create_business_dir(&current_dir)?,
+            self.create_architecture_dir(&current_dir)?,
+        ];
 
-        Ok(())
+        if let Some(template_dir) = &self.template_dir {
+            paths.extend(self.copy_template(template_dir, &current_dir, &project)?);
+        }
+
+        Ok(paths)
     }
 }
 
+/// `load_project` reads the project file located in the given directory through the
+/// document processor, if there is one.
+pub(crate) fn load_project(
+    document: &impl FsProcessor,
+    current_dir: &Path,
+) -> Result<Option<CoreProject>, ProjectError> {
+    let file_path = current_dir.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
+    if !document.exists(FilePath::from(file_path.clone())) {
+        return Ok(None);
+    }
+
+    let content = document.read_to_string(FilePath::from(file_path))?;
+    let project =
+        serde_json::from_str(&content).map_err(|err| ProjectError::ValidationError(err.into()))?;
+
+    Ok(Some(project))
+}
+
+/// Substitutes the template placeholders, content which is not text is returned as is.
+fn render_template(content: Vec<u8>, project: &CoreProject) -> Vec<u8> {
+    if content.contains(&0) {
+        return content;
+    }
+
+    match String::from_utf8(content) {
+        Ok(text) => {
+            let description = project
+                .description
+                .as_ref()
+                .map(|desc| desc.as_str())
+                .unwrap_or_default();
+
+            text.replace(TEMPLATE_PROJECT_NAME, project.name.as_str())
+                .replace(TEMPLATE_DESCRIPTION, description)
+                .into_bytes()
+        }
+        Err(err) => err.into_bytes(),
+    }
+}
+
+/// `PackageManifest` is the name and description of a package detected in a directory.
+#[derive(Debug, Clone, PartialEq)]
+struct PackageManifest {
+    name: String,
+    description: Option<String>,
+}
+
+/// `detect_manifest` reads the package name and description from the `Cargo.toml` or,
+/// failing that, the `package.json` of the given directory.
+fn detect_manifest(dir: &Path) -> Option<PackageManifest> {
+    let cargo = fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| parse_cargo_manifest(&content));
+
+    cargo.or_else(|| {
+        let content = fs::read_to_string(dir.join("package.json")).ok()?;
+        let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let field = |key: &str| {
+            package[key]
+                .as_str()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+
+        Some(PackageManifest {
+            name: field("name")?,
+            description: field("description"),
+        })
+    })
+}
+
+/// Reads the `[package]` name and description of a `Cargo.toml`.
+///
+/// Only single line string values are supported, which covers what `cargo new` writes,
+/// a workspace inherited value like `description.workspace = true` is ignored.
+fn parse_cargo_manifest(content: &str) -> Option<PackageManifest> {
+    let mut in_package = false;
+    let mut name = None;
+    let mut description = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=').filter(|_| in_package) else {
+            continue;
+        };
+
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .or_else(|| {
+                value
+                    .strip_prefix('\'')
+                    .and_then(|value| value.strip_suffix('\''))
+            })
+            .filter(|value| !value.trim().is_empty())
+            .map(String::from);
+
+        match key.trim() {
+            "name" => name = value,
+            "description" => description = value,
+            _ => {}
+        }
+    }
+
+    Some(PackageManifest {
+        name: name?,
+        description,
+    })
+}
+
+/// `generated_paths` lists the generated artifacts of the project in the given directory,
+/// sorted: every entry of the architectures directory and every `.raw` or `.prompt` file
+/// under the businesses directory. Business definitions and the registry never match.
+fn generated_paths(current_dir: &Path) -> Result<Vec<PathBuf>, ProjectError> {
+    let mut paths = vec![];
+
+    let architecture_dir = current_dir.join(PROJECT_ARCHITECTURE_DIR_NAME);
+    if architecture_dir.is_dir() {
+        for entry in read_dir(&architecture_dir).map_err(ProjectError::FsError)? {
+            paths.push(entry.map_err(ProjectError::FsError)?.path());
+        }
+    }
+
+    let business_dir = current_dir.join(PROJECT_BUSINESS_DIR_NAME);
+    if business_dir.is_dir() {
+        find_generated_files(&business_dir, &mut paths)?;
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Collects the generated files of a directory tree, symlinked directories aren't followed.
+fn find_generated_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), ProjectError> {
+    for entry in read_dir(dir).map_err(ProjectError::FsError)? {
+        let entry = entry.map_err(ProjectError::FsError)?;
+        let file_type = entry.file_type().map_err(ProjectError::FsError)?;
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            find_generated_files(&path, paths)?;
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| GENERATED_EXTENSIONS.contains(&extension))
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes a generated path, a symlink is removed itself rather than what it points to.
+fn remove_generated(path: &Path) -> Result<(), ProjectError> {
+    let metadata = fs::symlink_metadata(path).map_err(ProjectError::FsError)?;
+    let result = match metadata.is_dir() {
+        true => fs::remove_dir_all(path),
+        false => fs::remove_file(path),
+    };
+
+    result.map_err(ProjectError::FsError)
+}
+
+/// `default_author` reads the current user name from the environment, ignoring empty values.
+fn default_author() -> Option<String> {
+    ["USER", "USERNAME"]
+        .iter()
+        .filter_map(|key| env::var(key).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
 #[derive(Debug)]
 pub(crate) struct Handler {
-    app: ProjectApp<ProjectBuilderImpl>,
+    document: DocumentProcessorAdapter,
+    template_dir: Option<PathBuf>,
 }
 
 impl Handler {
     #[instrument]
     pub fn new() -> Self {
         Handler {
-            app: ProjectApp::new(ProjectBuilderImpl),
+            document: DocumentProcessorAdapter::new(),
+            template_dir: None,
         }
     }
 
+    /// `with_template_dir` copies the given directory into every initiated project.
+    pub fn with_template_dir(mut self, template_dir: Option<PathBuf>) -> Self {
+        self.template_dir = template_dir;
+        self
+    }
+
+    /// Builds the project app initiating projects in the current directory.
+    fn app(
+        &self,
+    ) -> Result<
+        ProjectApp<ProjectBuilderImpl<PathBufAdapter, DocumentProcessorAdapter>>,
+        ProjectError,
+    > {
+        let current_dir = env::current_dir().map_err(ProjectError::FsError)?;
+        let builder =
+            ProjectBuilderImpl::new(PathBufAdapter::new(current_dir), self.document.clone())
+                .with_template_dir(self.template_dir.clone());
+
+        Ok(ProjectApp::new(builder))
+    }
+
     #[instrument(skip_all)]
-    pub fn init(&self, name: String, desc: Option<String>) {
-        self.app
-            .init(name.into(), desc.map(|d| d.into()))
-            .unwrap_or_else(|err| {
-                error!("Failed to initiate project: {}", err);
-            });
+    pub fn init(
+        &self,
+        name: Option<String>,
+        desc: Option<String>,
+        author: Option<String>,
+        force: bool,
+        output: OutputFormat,
+    ) -> Result<(), ProjectError> {
+        let (name, desc) = match name {
+            Some(name) => (name, desc),
+            None => {
+                let current_dir = env::current_dir().map_err(ProjectError::FsError)?;
+                let manifest = detect_manifest(&current_dir).ok_or_else(|| {
+                    ProjectError::InitiateError(
+                        "--name is required when no Cargo.toml or package.json names the project"
+                            .to_string(),
+                    )
+                })?;
+
+                info!("Using the detected package name {}", manifest.name);
+                (manifest.name, desc.or(manifest.description))
+            }
+        };
+
+        let author = author.or_else(default_author);
+        let (project, paths) =
+            self.app()?
+                .init(name.into(), desc.map(|d| d.into()), author, force)?;
+
+        match output {
+            OutputFormat::Text => {
+                match &project.author {
+                    Some(author) => info!("Project {} initiated by {}", project.name, author),
+                    None => info!("Project {} initiated", project.name),
+                }
+                for item in &paths {
+                    let status = if item.created { "created" } else { "exists" };
+                    info!("{}: {}", status, item.path.display());
+                }
+            }
+            OutputFormat::Json => {
+                let summary = InitSummary {
+                    name: project.name.to_string(),
+                    created_at: project.created_at,
+                    author: project.author,
+                    paths,
+                };
+                match summary.to_json() {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => error!("Failed to render project summary: {}", err),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `clean` removes the generated artifacts of the project in the current directory.
+    #[instrument(skip_all)]
+    pub fn clean(&self, dry_run: bool, output: OutputFormat) -> Result<(), ProjectError> {
+        let current_dir = env::current_dir().map_err(ProjectError::FsError)?;
+        if load_project(&self.document, &current_dir)?.is_none() {
+            return Err(ProjectError::NotInitialized(
+                current_dir.display().to_string(),
+            ));
+        }
+
+        let paths = generated_paths(&current_dir)?;
+        if !dry_run {
+            for path in &paths {
+                debug!("Removing generated path {:?}", path);
+                remove_generated(path)?;
+            }
+        }
+
+        match output {
+            OutputFormat::Text => {
+                let action = if dry_run { "would remove" } else { "removed" };
+                for path in &paths {
+                    println!("{}: {}", action, path.display());
+                }
+                if paths.is_empty() {
+                    println!("nothing to clean");
+                }
+            }
+            OutputFormat::Json => {
+                let summary = CleanSummary { dry_run, paths };
+                let json = summary.to_json().map_err(ProjectError::ValidationError)?;
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `status` prints the project of the current directory.
+    #[instrument(skip_all)]
+    pub fn status(&self, output: OutputFormat) -> Result<(), ProjectError> {
+        let current_dir = env::current_dir().map_err(ProjectError::FsError)?;
+        let mut project = load_project(&self.document, &current_dir)?
+            .ok_or_else(|| ProjectError::NotInitialized(current_dir.display().to_string()))?;
+
+        match output {
+            OutputFormat::Text => {
+                println!("name: {}", project.name);
+                if let Some(desc) = &project.description {
+                    println!("description: {}", desc);
+                }
+                if let Some(author) = &project.author {
+                    println!("author: {}", author);
+                }
+                println!("created_at: {}", project.created_at.to_rfc3339());
+                println!("updated_at: {}", project.last_updated().to_rfc3339());
+            }
+            OutputFormat::Json => {
+                project.updated_at = Some(project.last_updated());
+                let json = project.to_json().map_err(ProjectError::ValidationError)?;
+                println!("{}", json);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::document::memory::MemoryFs;
+    use crate::core::project::types::Name;
+    use crate::core::registry::memory::MemoryDir;
+
+    /// A builder initiating projects on disk, in the given directory.
+    fn disk_builder(
+        current_dir: &Path,
+    ) -> ProjectBuilderImpl<PathBufAdapter, DocumentProcessorAdapter> {
+        ProjectBuilderImpl::new(
+            PathBufAdapter::new(current_dir.to_path_buf()),
+            DocumentProcessorAdapter::new(),
+        )
+    }
+
+    const MEMORY_ROOT: &str = "/memory/shop";
+
+    /// A fixed time recorded as the project creation time.
+    fn fixed_clock() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// A project app initiating projects in memory, at a fixed time.
+    fn memory_app(fs: MemoryFs) -> ProjectApp<ProjectBuilderImpl<MemoryDir, MemoryFs>> {
+        ProjectApp::new(ProjectBuilderImpl::new(MemoryDir::new(MEMORY_ROOT), fs))
+            .with_clock(fixed_clock)
+    }
+
+    #[test]
+    fn test_init_in_memory() {
+        let fs = MemoryFs::new().with_dir(MEMORY_ROOT);
+        let root = PathBuf::from(MEMORY_ROOT);
+        let project_file = root.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
+
+        let (project, paths) = memory_app(fs.clone())
+            .init(Name::from("shop"), None, Some("alice".to_string()), false)
+            .unwrap();
+        assert_eq!(project.created_at, fixed_clock());
+        assert_eq!(
+            paths,
+            vec![
+                InitiatedPath::new(root.join(PROJECT_DIR_NAME), true),
+                InitiatedPath::new(project_file.clone(), true),
+                InitiatedPath::new(root.join(PROJECT_DIR_NAME).join(".gitignore"), true),
+                InitiatedPath::new(root.join(PROJECT_BUSINESS_DIR_NAME), true),
+                InitiatedPath::new(root.join(PROJECT_ARCHITECTURE_DIR_NAME), true),
+            ]
+        );
+
+        let saved: CoreProject = serde_json::from_str(&fs.file(&project_file).unwrap()).unwrap();
+        assert_eq!(saved.name.as_str(), "shop");
+        assert_eq!(saved.author.as_deref(), Some("alice"));
+        assert_eq!(saved.created_at, fixed_clock());
+        assert!(saved.updated_at.is_none());
+        assert_eq!(
+            fs.file(root.join(PROJECT_DIR_NAME).join(".gitignore"))
+                .unwrap(),
+            PROJECT_CREDENTIAL_NAME
+        );
+        assert!(fs.is_dir(root.join(PROJECT_BUSINESS_DIR_NAME)));
+        assert!(fs.is_dir(root.join(PROJECT_ARCHITECTURE_DIR_NAME)));
+    }
+
+    #[test]
+    fn test_init_in_memory_forced() {
+        let root = PathBuf::from(MEMORY_ROOT);
+        let project_file = root.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
+        let fs = MemoryFs::new()
+            .with_dir(MEMORY_ROOT)
+            .with_dir(root.join(PROJECT_DIR_NAME))
+            .with_file(
+                &project_file,
+                r#"{"name":"first","created_at":"2024-06-01T00:00:00Z"}"#,
+            );
+
+        let result = memory_app(fs.clone()).init(Name::from("second"), None, None, false);
+        assert!(matches!(result, Err(ProjectError::AlreadyInitialized(_))));
+
+        let (_, paths) = memory_app(fs.clone())
+            .init(Name::from("second"), None, None, true)
+            .unwrap();
+        assert_eq!(
+            paths[0],
+            InitiatedPath::new(root.join(PROJECT_DIR_NAME), false)
+        );
+        assert_eq!(paths[1], InitiatedPath::new(project_file.clone(), false));
+
+        let saved: CoreProject = serde_json::from_str(&fs.file(&project_file).unwrap()).unwrap();
+        assert_eq!(saved.name.as_str(), "second");
+        assert_eq!(saved.created_at.to_rfc3339(), "2024-06-01T00:00:00+00:00");
+        assert_eq!(saved.updated_at, Some(fixed_clock()));
+    }
+
+    #[test]
+    fn test_init_in_memory_forced_corrupt_project() {
+        let root = PathBuf::from(MEMORY_ROOT);
+        let project_file = root.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME);
+        let fs = MemoryFs::new()
+            .with_dir(MEMORY_ROOT)
+            .with_dir(root.join(PROJECT_DIR_NAME))
+            .with_file(&project_file, r#"{"name":"#);
+
+        let result = memory_app(fs.clone()).init(Name::from("second"), None, None, true);
+        assert!(matches!(result, Err(ProjectError::InitiateError(_))));
+        assert_eq!(fs.file(&project_file).unwrap(), r#"{"name":"#);
+        assert!(matches!(
+            load_project(&fs, &root),
+            Err(ProjectError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_initiate_already_initialized() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path().to_path_buf();
+        let builder = disk_builder(&current_dir);
+
+        let project = CoreProject::new(Name::from("first"), None);
+        assert!(builder.initiate(project, false).is_ok());
+
+        let project = CoreProject::new(Name::from("second"), None);
+        let result = builder.initiate(project, false);
+        assert!(matches!(result, Err(ProjectError::AlreadyInitialized(_))));
+
+        let loaded = load_project(&DocumentProcessorAdapter::new(), &current_dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.name.as_str(), "first");
+    }
+
+    #[test]
+    fn test_generated_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path();
+        let builder = disk_builder(current_dir);
+        let project = CoreProject::new(Name::from("shop"), None);
+        builder.initiate(project, false).unwrap();
+        assert!(generated_paths(current_dir).unwrap().is_empty());
+
+        let architecture_dir = current_dir.join(PROJECT_ARCHITECTURE_DIR_NAME);
+        let business_dir = current_dir.join(PROJECT_BUSINESS_DIR_NAME);
+        fs::create_dir_all(architecture_dir.join("order")).unwrap();
+        fs::write(architecture_dir.join("order").join("0.1.0.md"), "# C4").unwrap();
+        fs::create_dir(business_dir.join("order")).unwrap();
+        fs::write(business_dir.join("order").join("0.1.0.md"), "# Order").unwrap();
+        fs::write(business_dir.join("order").join("0.1.0.raw"), "{}").unwrap();
+        fs::write(business_dir.join("order").join("0.1.0.prompt"), "Analyze").unwrap();
+        fs::write(business_dir.join("registry.json"), "{}").unwrap();
+
+        let paths = generated_paths(current_dir).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                architecture_dir.join("order"),
+                business_dir.join("order").join("0.1.0.prompt"),
+                business_dir.join("order").join("0.1.0.raw"),
+            ]
+        );
+
+        for path in &paths {
+            remove_generated(path).unwrap();
+        }
+        assert!(architecture_dir.is_dir());
+        assert!(!architecture_dir.join("order").exists());
+        assert!(!business_dir.join("order").join("0.1.0.raw").exists());
+        assert!(business_dir.join("order").join("0.1.0.md").exists());
+        assert!(business_dir.join("registry.json").exists());
+        assert!(generated_paths(current_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_cargo_manifest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(detect_manifest(temp_dir.path()).is_none());
+
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            r#"[package]
+name = "order-service"
+version = "0.1.0"
+description = 'Takes the orders'
+
+[dependencies]
+name = "not-the-package"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "order-web"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            detect_manifest(temp_dir.path()),
+            Some(PackageManifest {
+                name: "order-service".to_string(),
+                description: Some("Takes the orders".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_detect_package_json_manifest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        // a virtual workspace manifest has no package to name the project after
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"order\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "order-web", "description": "", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            detect_manifest(temp_dir.path()),
+            Some(PackageManifest {
+                name: "order-web".to_string(),
+                description: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_initiate_with_template_dir() {
+        let template_dir = tempfile::tempdir().unwrap();
+        let template_path = template_dir.path();
+        fs::create_dir(template_path.join("docs")).unwrap();
+        fs::write(
+            template_path.join("README.md"),
+            "# {{project_name}}\n\n{{description}}\n",
+        )
+        .unwrap();
+        fs::write(
+            template_path.join("docs").join("logo.bin"),
+            b"{{project_name}}\0\xff",
+        )
+        .unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path().to_path_buf();
+        let builder =
+            disk_builder(&current_dir).with_template_dir(Some(template_path.to_path_buf()));
+
+        let project = CoreProject::new(Name::from("shop"), Some("An online shop".into()));
+        let paths = builder.initiate(project, false).unwrap();
+        assert!(paths.contains(&InitiatedPath::new(current_dir.join("README.md"), true)));
+        assert!(paths.contains(&InitiatedPath::new(current_dir.join("docs"), true)));
+
+        assert_eq!(
+            fs::read_to_string(current_dir.join("README.md")).unwrap(),
+            "# shop\n\nAn online shop\n"
+        );
+        assert_eq!(
+            fs::read(current_dir.join("docs").join("logo.bin")).unwrap(),
+            b"{{project_name}}\0\xff"
+        );
+        assert!(current_dir
+            .join(PROJECT_DIR_NAME)
+            .join(PROJECT_FILE_NAME)
+            .exists());
+    }
+
+    #[test]
+    fn test_initiate_missing_template_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path().to_path_buf();
+        let builder = disk_builder(&current_dir)
+            .with_template_dir(Some(current_dir.join("missing-template")));
+
+        let project = CoreProject::new(Name::from("shop"), None);
+        let result = builder.initiate(project, false);
+        assert!(matches!(result, Err(ProjectError::InitiateError(_))));
+        assert!(!current_dir.join(PROJECT_DIR_NAME).exists());
+    }
+
+    #[test]
+    fn test_initiate_forced() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let current_dir = temp_dir.path().to_path_buf();
+        let builder = disk_builder(&current_dir);
+
+        let project = CoreProject::new(Name::from("first"), None);
+        let first_created_at = project.created_at;
+        builder.initiate(project, false).unwrap();
+
+        let project = CoreProject::new(Name::from("second"), None);
+        let paths = builder.initiate(project, true).unwrap();
+        assert!(paths.iter().all(|item| !item.created));
+
+        let loaded = load_project(&DocumentProcessorAdapter::new(), &current_dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.name.as_str(), "second");
+        assert_eq!(loaded.created_at, first_created_at);
+        assert!(loaded.updated_at.is_some());
     }
 }
@@ -0,0 +1,69 @@
+use clap::Args;
+use serde::Serialize;
+use tracing::error;
+
+use crate::cli::OutputFormat;
+use crate::core::types::ToJSON;
+
+#[derive(Args)]
+pub(crate) struct VersionArgs {
+    /// Also print the git commit, build date and rustc version of this build
+    #[arg(long)]
+    pub verbose: bool,
+}
+
+/// `BuildInfo` describes the running binary, the metadata is captured by the build script.
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    version: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_commit: Option<&'static str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    build_date: Option<&'static str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rustc_version: Option<&'static str>,
+}
+
+impl BuildInfo {
+    fn new(verbose: bool) -> Self {
+        let metadata = |value: &'static str| verbose.then_some(value);
+
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: metadata(env!("DDAI_GIT_COMMIT")),
+            build_date: metadata(env!("DDAI_BUILD_DATE")),
+            rustc_version: metadata(env!("DDAI_RUSTC_VERSION")),
+        }
+    }
+}
+
+impl ToJSON for BuildInfo {}
+
+#[derive(Debug)]
+pub(crate) struct Handler;
+
+impl Handler {
+    pub fn show(&self, args: VersionArgs, output: OutputFormat) {
+        let info = BuildInfo::new(args.verbose);
+
+        match output {
+            OutputFormat::Text => {
+                println!("ddai {}", info.version);
+                if let (Some(commit), Some(date), Some(rustc)) =
+                    (info.git_commit, info.build_date, info.rustc_version)
+                {
+                    println!("commit: {}", commit);
+                    println!("build date: {}", date);
+                    println!("rustc: {}", rustc);
+                }
+            }
+            OutputFormat::Json => match info.to_json() {
+                Ok(json) => println!("{}", json),
+                Err(err) => error!("Failed to render build info: {}", err),
+            },
+        }
+    }
+}
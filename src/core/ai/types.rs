@@ -0,0 +1,238 @@
+use serde::Serialize;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::core::types::ToJSON;
+
+/// Rough number of characters per token, used to estimate prompt sizes without a tokenizer.
+pub(crate) const CHARS_PER_TOKEN: usize = 4;
+
+/// Number of times a failed model request is retried by default, see [`RetryingProvider`].
+pub(crate) const DEFAULT_RETRIES: u32 = 2;
+
+#[derive(Debug, Error)]
+pub(crate) enum AiError {
+    #[error("[ai error] no model provider is configured")]
+    NotConfigured,
+
+    #[allow(dead_code)]
+    #[error("[ai error] request failed: {0}")]
+    RequestFailed(String),
+
+    /// The model refused the prompt or the provider filtered its content, carrying the
+    /// provider's reason. Sending the same prompt again won't help, so it is never retried.
+    #[allow(dead_code)]
+    #[error("[ai error] content filtered by the provider: {0}")]
+    ContentFiltered(String),
+}
+
+/// `LlmProvider` is the interface to a large language model.
+///
+/// It takes a fully rendered prompt and returns the raw model output, so the providers
+/// don't need to know anything about business definitions.
+pub(crate) trait LlmProvider {
+    fn complete(&self, prompt: &str) -> Result<String, AiError>;
+}
+
+/// `NoProvider` stands for the absence of a model provider.
+///
+/// It has no values, so an app typed with it can never call a model.
+#[derive(Debug, Clone)]
+pub(crate) enum NoProvider {}
+
+impl LlmProvider for NoProvider {
+    fn complete(&self, _prompt: &str) -> Result<String, AiError> {
+        match *self {}
+    }
+}
+
+/// `RetryingProvider` retries the failed requests of another provider.
+///
+/// It makes [`DEFAULT_RETRIES`] retries unless told otherwise, `0` means a single attempt
+/// whose error is returned as is. Only [`AiError::RequestFailed`] is retried, a
+/// [`AiError::ContentFiltered`] refusal is returned right away, and the error of the last
+/// attempt is the one returned.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub(crate) struct RetryingProvider<P: LlmProvider> {
+    inner: P,
+    retries: u32,
+}
+
+#[allow(dead_code)]
+impl<P: LlmProvider> RetryingProvider<P> {
+    pub(crate) fn new(inner: P) -> Self {
+        RetryingProvider {
+            inner,
+            retries: DEFAULT_RETRIES,
+        }
+    }
+
+    pub(crate) fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+}
+
+impl<P: LlmProvider> LlmProvider for RetryingProvider<P> {
+    fn complete(&self, prompt: &str) -> Result<String, AiError> {
+        let mut attempt = 0;
+        loop {
+            match self.inner.complete(prompt) {
+                Err(AiError::RequestFailed(reason)) if attempt < self.retries => {
+                    attempt += 1;
+                    warn!(
+                        "Model request failed, retrying {}/{}: {}",
+                        attempt, self.retries, reason
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// `PromptEstimate` is an approximate size, and optionally cost, of a rendered prompt.
+///
+/// The token count comes from the [`CHARS_PER_TOKEN`] heuristic, not from a real tokenizer,
+/// so it is only meant as an order of magnitude before calling a model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct PromptEstimate {
+    pub(crate) tokens: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cost: Option<f64>,
+}
+
+impl PromptEstimate {
+    pub(crate) fn new(prompt: &str) -> Self {
+        PromptEstimate {
+            tokens: prompt.chars().count().div_ceil(CHARS_PER_TOKEN),
+            cost: None,
+        }
+    }
+
+    /// `with_price` derives the cost from a price per million prompt tokens.
+    pub(crate) fn with_price(mut self, price_per_million_tokens: f64) -> Self {
+        self.cost = Some(self.tokens as f64 * price_per_million_tokens / 1_000_000.0);
+        self
+    }
+}
+
+impl ToJSON for PromptEstimate {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::{mock, predicate::*, Sequence};
+
+    mock! {
+        FakeLlmProvider {}
+
+        impl LlmProvider for FakeLlmProvider {
+            fn complete(&self, prompt: &str) -> Result<String, AiError>;
+        }
+    }
+
+    mod test_retrying_provider {
+        use super::*;
+
+        #[test]
+        fn test_no_retries_is_a_single_attempt() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .with(eq("prompt"))
+                .times(1)
+                .returning(|_| Err(AiError::RequestFailed("rate limited".to_string())));
+
+            let provider = RetryingProvider::new(provider).with_retries(0);
+            match provider.complete("prompt") {
+                Err(AiError::RequestFailed(reason)) => assert_eq!(reason, "rate limited"),
+                _ => panic!("Expected RequestFailed"),
+            }
+        }
+
+        #[test]
+        fn test_retries_until_success() {
+            let mut sequence = Sequence::new();
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .times(2)
+                .in_sequence(&mut sequence)
+                .returning(|_| Err(AiError::RequestFailed("timeout".to_string())));
+            provider
+                .expect_complete()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| Ok("analysis".to_string()));
+
+            let provider = RetryingProvider::new(provider);
+            assert_eq!(provider.complete("prompt").unwrap(), "analysis");
+        }
+
+        #[test]
+        fn test_retries_exhausted() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .times(DEFAULT_RETRIES as usize + 1)
+                .returning(|_| Err(AiError::RequestFailed("timeout".to_string())));
+
+            let provider = RetryingProvider::new(provider);
+            assert!(matches!(
+                provider.complete("prompt"),
+                Err(AiError::RequestFailed(_))
+            ));
+        }
+
+        #[test]
+        fn test_not_configured_is_not_retried() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .times(1)
+                .returning(|_| Err(AiError::NotConfigured));
+
+            let provider = RetryingProvider::new(provider);
+            assert!(matches!(
+                provider.complete("prompt"),
+                Err(AiError::NotConfigured)
+            ));
+        }
+
+        #[test]
+        fn test_content_filtered_is_not_retried() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .times(1)
+                .returning(|_| Err(AiError::ContentFiltered("content_policy".to_string())));
+
+            let provider = RetryingProvider::new(provider);
+            match provider.complete("prompt") {
+                Err(AiError::ContentFiltered(reason)) => assert_eq!(reason, "content_policy"),
+                _ => panic!("Expected ContentFiltered"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_prompt_estimate() {
+        assert_eq!(PromptEstimate::new("").tokens, 0);
+        assert_eq!(PromptEstimate::new("abcd").tokens, 1);
+        assert_eq!(PromptEstimate::new("abcde").tokens, 2);
+
+        // characters are counted, not bytes
+        assert_eq!(PromptEstimate::new("ééé").tokens, 1);
+    }
+
+    #[test]
+    fn test_prompt_estimate_with_price() {
+        let estimate = PromptEstimate::new(&"a".repeat(4_000)).with_price(3.0);
+        assert_eq!(estimate.tokens, 1_000);
+        assert_eq!(estimate.cost, Some(0.003));
+        assert!(PromptEstimate::new("abcd").cost.is_none());
+    }
+}
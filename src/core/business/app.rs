@@ -1,19 +1,37 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use tracing::{debug, info, warn};
+
 use crate::core::types::{validate, PathBufWrapper};
 
+use crate::core::ai::types::{AiError, LlmProvider, NoProvider, PromptEstimate};
+
+use crate::core::document::types::DocumentError;
 use crate::core::registry::manager::Manager as RegistryManager;
-use crate::core::registry::types::{FileVersion, Processor as RegistryProcessor};
+use crate::core::registry::types::{
+    FileItem, FileLocation, FileVersion, Processor as RegistryProcessor, RegistryInfo,
+    RegistryProblem, VersionPart,
+};
 
-use crate::core::business::types::{BusinessError, Definition, Processor};
+use crate::core::business::markdown::{missing_sections, split_sections};
+use crate::core::business::types::{
+    AnalyzeParameters, BusinessError, ChunkedAnalysis, DefineReport, Definition, DefinitionFile,
+    ExportReport, LintReport, Processor, MAX_REGISTRY_SUMMARY_CHARS,
+};
 
 #[derive(Debug, Clone)]
-pub(crate) struct App<P, RP, PW>
+pub(crate) struct App<P, RP, PW, L = NoProvider>
 where
     P: Processor,
     RP: RegistryProcessor,
     PW: PathBufWrapper,
+    L: LlmProvider,
 {
     processor: P,
     registry: RegistryManager<RP, PW>,
+    provider: Option<L>,
+    silent_duplicate: bool,
 }
 
 impl<P, RP, PW> App<P, RP, PW>
@@ -22,20 +40,58 @@ where
     RP: RegistryProcessor,
     PW: PathBufWrapper,
 {
+    /// `new` builds an app without a model provider, see [`App::with_provider`].
     pub(crate) fn new(processor: P, registry: RegistryManager<RP, PW>) -> Self {
         App {
             processor,
             registry,
+            provider: None,
+            silent_duplicate: false,
+        }
+    }
+}
+
+impl<P, RP, PW, L> App<P, RP, PW, L>
+where
+    P: Processor,
+    RP: RegistryProcessor,
+    PW: PathBufWrapper,
+    L: LlmProvider,
+{
+    /// `with_provider` sets the model provider used by [`App::analyze`].
+    #[allow(dead_code)]
+    pub(crate) fn with_provider<T: LlmProvider>(self, provider: T) -> App<P, RP, PW, T> {
+        App {
+            processor: self.processor,
+            registry: self.registry,
+            provider: Some(provider),
+            silent_duplicate: self.silent_duplicate,
         }
     }
 
+    /// `with_silent_duplicate` makes defining a version whose file already exists only
+    /// register it, instead of failing with [`BusinessError::AlreadyExists`].
+    pub(crate) fn with_silent_duplicate(mut self, silent_duplicate: bool) -> Self {
+        self.silent_duplicate = silent_duplicate;
+        self
+    }
+
+    /// `define` creates a business definition version starting with the given content,
+    /// empty for a blank one, and registers it. An already existing file keeps its content.
+    ///
+    /// The location only applies to a new definition, an already registered one keeps
+    /// the location recorded in the registry so all of its versions stay together.
+    /// It reports the resolved version, the genesis version when none was given, the path
+    /// of its file and whether that version was newly registered.
     pub(crate) fn define(
         &self,
         definition: Definition,
         version: Option<FileVersion>,
-    ) -> Result<(), BusinessError> {
-        let _ =
-            validate(&definition).map_err(|e| BusinessError::InvalidDefinition(e.to_string()))?;
+        location: impl Into<FileLocation>,
+        content: &str,
+    ) -> Result<DefineReport, BusinessError> {
+        let location = location.into();
+        validate(&definition).map_err(|e| BusinessError::InvalidDefinition(e.to_string()))?;
 
         // if user does not provide a version, we will use the default version
         let file_version = match version {
@@ -43,27 +99,457 @@ where
             None => FileVersion::new(),
         };
 
-        // start defining the business definition with its version
-        let _ = self
-            .processor
-            .define(definition.clone(), file_version.clone())?;
+        // checked before writing anything, since colliding names share their files on
+        // case-insensitive filesystems, and a capped registry would leave the new file untracked
+        self.registry.check_collision(&definition.to_filename())?;
+        self.registry
+            .check_capacity(&definition.to_filename(), &file_version)?;
+
+        // a missing or unreadable registry is reported by `update_registry` below
+        let location = match self.registry.get_file(definition.to_filename()) {
+            Ok(Some(file_item)) if file_item.location() != location => {
+                warn!(
+                    "Definition {} keeps its registered {:?} layout{}",
+                    definition.stem(),
+                    file_item.layout,
+                    file_item
+                        .dir
+                        .as_ref()
+                        .map(|dir| format!(" in {}", dir.display()))
+                        .unwrap_or_default()
+                );
+                file_item.location()
+            }
+            _ => location,
+        };
+
+        // start defining the business definition with its version, an existing file is
+        // left untouched
+        let path = if !self.processor.exists(&definition, &file_version, &location) {
+            self.processor.define(
+                definition.clone(),
+                file_version.clone(),
+                location.clone(),
+                content,
+            )?
+        } else if self.silent_duplicate {
+            debug!(
+                "Definition {} {} already exists, leaving it untouched",
+                definition.stem(),
+                file_version
+            );
+            self.processor.path(&definition, &file_version, &location)
+        } else {
+            return Err(BusinessError::AlreadyExists(format!(
+                "{} {}",
+                definition.stem(),
+                file_version
+            )));
+        };
 
         // once the business def defined, we need to update registry
+        // the definition file is always written first, so a failure here leaves an
+        // untracked file which a later define of the same version with a silent
+        // duplicate registers again
         self.registry
-            .update_registry(definition.to_filename(), file_version)
-            .map_err(|err| BusinessError::RegistryError(err))
+            .update_registry(definition.to_filename(), file_version.clone(), location)
+            .map_err(|err| {
+                warn!(
+                    "Definition {} {} was created but the registry was not updated: {}",
+                    definition.stem(),
+                    file_version,
+                    err
+                );
+                BusinessError::RegistryError(err)
+            })
+            .map(|added| DefineReport {
+                version: file_version,
+                path,
+                added,
+            })
+    }
+
+    /// `lint` checks every registered version of a business definition for the required sections.
+    ///
+    /// A section counts as missing when its heading is absent or when it has no content.
+    /// One [`LintReport`] is returned per version, in registry order.
+    pub(crate) fn lint(
+        &self,
+        definition: Definition,
+        required: &[String],
+    ) -> Result<Vec<LintReport>, BusinessError> {
+        let file_item = self.get(definition.clone())?;
+        let location = file_item.location();
+
+        file_item
+            .versions
+            .into_iter()
+            .map(|version| {
+                let content = self.read_version(&definition, &version, location.clone())?;
+                Ok(LintReport {
+                    version,
+                    missing: missing_sections(&content, required),
+                })
+            })
+            .collect()
+    }
+
+    /// `list` returns every registered business definition, or only those of the given
+    /// domain, in registry order.
+    pub(crate) fn list(&self, domain: Option<&str>) -> Result<Vec<FileItem>, BusinessError> {
+        let mut files = self.registry.snapshot()?.files;
+        if let Some(domain) = domain {
+            files.retain(|file| file.domain.as_deref() == Some(domain));
+        }
+
+        Ok(files)
+    }
+
+    /// `set_domain` groups a registered business definition under the given domain.
+    ///
+    /// It returns `true` when the domain changed.
+    pub(crate) fn set_domain(
+        &self,
+        definition: Definition,
+        domain: String,
+    ) -> Result<bool, BusinessError> {
+        let file_item = self.get(definition)?;
+        Ok(self.registry.set_domain(&file_item.name, domain)?)
+    }
+
+    /// `registry_info` describes the registry file as found on disk, `None` when there is none.
+    pub(crate) fn registry_info(&self) -> Result<Option<RegistryInfo>, BusinessError> {
+        Ok(self.registry.inspect()?)
+    }
+
+    /// `registry_problems` validates the registry content as is, every problem found is
+    /// returned along with its location. The files it references are not checked.
+    pub(crate) fn registry_problems(&self) -> Result<Vec<RegistryProblem>, BusinessError> {
+        Ok(self.registry.snapshot()?.problems())
+    }
+
+    /// `latest_updated` returns the business definition a version was most recently added
+    /// to, `None` when no addition time was recorded.
+    pub(crate) fn latest_updated(&self) -> Result<Option<FileItem>, BusinessError> {
+        Ok(self.registry.snapshot()?.latest_updated().cloned())
+    }
+
+    /// `version_histogram` counts the registered business definitions per number of versions.
+    pub(crate) fn version_histogram(&self) -> Result<BTreeMap<usize, usize>, BusinessError> {
+        Ok(self.registry.snapshot()?.version_histogram())
+    }
+
+    /// `export_registry` copies the registry and every definition version it references
+    /// into the target directory, keeping the same layout.
+    ///
+    /// Versions whose file is missing are reported as dangling instead of failing the export.
+    /// Definitions stored in a directory of their own are gathered in the target directory
+    /// as well, so the exported registry doesn't record their directory anymore.
+    pub(crate) fn export_registry(
+        &self,
+        target_dir: PathBuf,
+    ) -> Result<ExportReport, BusinessError> {
+        let mut registry = self.registry.snapshot()?;
+
+        let mut report = ExportReport::default();
+        for file_item in &registry.files {
+            let definition = Definition::from(&file_item.name);
+            for version in &file_item.versions {
+                let result = self.processor.export(
+                    definition.clone(),
+                    version.clone(),
+                    file_item.location(),
+                    target_dir.clone(),
+                );
+
+                match result {
+                    Ok(()) => report.exported += 1,
+                    Err(BusinessError::DocumentError(DocumentError::NotFound(_))) => report
+                        .dangling
+                        .push(format!("{} {}", definition.stem(), version)),
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        registry
+            .files
+            .iter_mut()
+            .for_each(|file_item| file_item.dir = None);
+        self.registry.export(&target_dir, registry)?;
+        Ok(report)
+    }
+
+    /// `orphans` lists the definition versions found on disk but missing from the registry.
+    pub(crate) fn orphans(&self) -> Result<Vec<DefinitionFile>, BusinessError> {
+        let registry = self.registry.snapshot()?;
+
+        let orphans = self
+            .processor
+            .scan()?
+            .into_iter()
+            .filter(|file| {
+                !registry
+                    .get_file(&file.definition.to_filename())
+                    .is_some_and(|item| item.versions.contains(&file.version))
+            })
+            .collect();
+
+        Ok(orphans)
+    }
+
+    /// `deduplicate` reports the duplicate business definitions and versions of the
+    /// registry, and removes them when `repair` is set.
+    pub(crate) fn deduplicate(&self, repair: bool) -> Result<Vec<String>, BusinessError> {
+        let duplicates = self.registry.deduplicate(repair)?;
+        if repair {
+            duplicates
+                .iter()
+                .for_each(|duplicate| info!("Removed the {} from the registry", duplicate));
+        }
+
+        Ok(duplicates)
+    }
+
+    /// `register` adds a definition version found on disk to the registry, the file itself
+    /// is left untouched. It returns `true` when the version was newly registered.
+    ///
+    /// A file stored elsewhere than its registered business is rejected, since the registry
+    /// could not locate it.
+    pub(crate) fn register(&self, file: &DefinitionFile) -> Result<bool, BusinessError> {
+        let name = file.definition.to_filename();
+        self.registry.check_collision(&name)?;
+
+        if let Some(item) = self.registry.snapshot()?.get_file(&name) {
+            if item.location() != FileLocation::from(file.layout) {
+                return Err(BusinessError::InvalidDefinition(format!(
+                    "{} {} is stored with the {:?} layout but the business uses {:?}{}",
+                    file.definition.stem(),
+                    file.version,
+                    file.layout,
+                    item.layout,
+                    item.dir
+                        .as_ref()
+                        .map(|dir| format!(" in {}", dir.display()))
+                        .unwrap_or_default()
+                )));
+            }
+        }
+
+        Ok(self
+            .registry
+            .update_registry(name, file.version.clone(), file.layout)?)
+    }
+
+    /// `get` returns the registry entry of a business definition.
+    pub(crate) fn get(&self, definition: Definition) -> Result<FileItem, BusinessError> {
+        validate(&definition).map_err(|e| BusinessError::InvalidDefinition(e.to_string()))?;
+
+        self.registry
+            .get_file(definition.to_filename())?
+            .ok_or_else(|| BusinessError::DefinitionNotFound(definition.stem().to_string()))
+    }
+
+    /// `versions` returns the registered versions of a business definition, oldest first.
+    pub(crate) fn versions(
+        &self,
+        definition: Definition,
+    ) -> Result<Vec<FileVersion>, BusinessError> {
+        let mut versions = self.get(definition)?.versions;
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// `resolve_version` returns the given version once checked against the registry, or
+    /// the newest registered one when it is omitted, so the analyze parameters can be built
+    /// without looking the current version up first.
+    pub(crate) fn resolve_version(
+        &self,
+        definition: Definition,
+        version: Option<FileVersion>,
+    ) -> Result<FileVersion, BusinessError> {
+        let file_item = self.get(definition.clone())?;
+
+        match version {
+            Some(v) if file_item.versions.contains(&v) => Ok(v),
+            Some(v) => Err(BusinessError::VersionNotFound(format!(
+                "{} {}",
+                definition.stem(),
+                v
+            ))),
+            None => file_item.get_last_version().ok_or_else(|| {
+                BusinessError::VersionNotFound(format!(
+                    "{} has no registered versions",
+                    definition.stem()
+                ))
+            }),
+        }
+    }
+
+    /// `read` loads the content of a registered business definition version.
+    ///
+    /// The version is resolved by [`App::resolve_version`], the newest registered one when
+    /// none is given, and returned along with the content.
+    pub(crate) fn read(
+        &self,
+        definition: Definition,
+        version: Option<FileVersion>,
+    ) -> Result<(FileVersion, String), BusinessError> {
+        let version = self.resolve_version(definition.clone(), version)?;
+        let location = self.get(definition.clone())?.location();
+
+        let content = self.read_version(&definition, &version, location)?;
+        Ok((version, content))
+    }
+
+    /// `analyze` sends a registered business definition version to the model provider.
+    ///
+    /// The parameters are validated and the definition content is loaded before the prompt
+    /// gets rendered, the raw model output is returned as is.
+    #[allow(dead_code)]
+    pub(crate) fn analyze(&self, params: AnalyzeParameters) -> Result<String, BusinessError> {
+        validate(&params)?;
+
+        let provider = self.provider.as_ref().ok_or(AiError::NotConfigured)?;
+        let (_, content) = self.read(params.definition.clone(), Some(params.version.clone()))?;
+
+        let prompt = self.prompt(&params, &content)?;
+        Ok(provider.complete(&prompt)?)
+    }
+
+    /// `analyze_chunked` analyzes a definition too large for a single model call.
+    ///
+    /// The definition is split at its section boundaries into chunks of at most
+    /// `max_chars` characters, see [`split_sections`]. Each chunk is sent with the same
+    /// parameters and registry context, and the outputs are joined in document order.
+    #[allow(dead_code)]
+    pub(crate) fn analyze_chunked(
+        &self,
+        params: AnalyzeParameters,
+        max_chars: usize,
+    ) -> Result<ChunkedAnalysis, BusinessError> {
+        validate(&params)?;
+
+        let provider = self.provider.as_ref().ok_or(AiError::NotConfigured)?;
+        let (_, content) = self.read(params.definition.clone(), Some(params.version.clone()))?;
+
+        let chunks = split_sections(&content, max_chars);
+        let total = chunks.len();
+        let mut outputs = Vec::with_capacity(total);
+        for (index, chunk) in chunks.iter().enumerate() {
+            debug!("Analyzing part {} of {}", index + 1, total);
+            let part = format!(
+                "(part {} of {}, the other parts are analyzed separately)\n\n{}",
+                index + 1,
+                total,
+                chunk
+            );
+            outputs.push(provider.complete(&self.prompt(&params, &part)?)?);
+        }
+
+        info!(
+            "Analyzed {} {} in {} parts",
+            params.definition.stem(),
+            params.version,
+            total
+        );
+        Ok(ChunkedAnalysis {
+            output: outputs.join("\n\n"),
+            chunks: total,
+        })
+    }
+
+    /// Renders the analyze prompt, with the registry summary when the parameters ask for it.
+    fn prompt(&self, params: &AnalyzeParameters, content: &str) -> Result<String, BusinessError> {
+        if !params.include_registry {
+            return Ok(params.to_prompt(content));
+        }
+
+        let summary = self
+            .registry
+            .snapshot()?
+            .summary(MAX_REGISTRY_SUMMARY_CHARS);
+        Ok(params.to_prompt_with_registry(content, Some(&summary)))
+    }
+
+    /// `estimate` renders the analyze prompt without calling the model and returns its
+    /// approximate size, priced when a price per million prompt tokens is given.
+    pub(crate) fn estimate(
+        &self,
+        params: AnalyzeParameters,
+        price_per_million_tokens: Option<f64>,
+    ) -> Result<PromptEstimate, BusinessError> {
+        validate(&params)?;
+
+        let (_, content) = self.read(params.definition.clone(), Some(params.version.clone()))?;
+        let estimate = PromptEstimate::new(&self.prompt(&params, &content)?);
+
+        Ok(match price_per_million_tokens {
+            Some(price) => estimate.with_price(price),
+            None => estimate,
+        })
+    }
+
+    /// `next_version` increments the given part of the last registered version.
+    pub(crate) fn next_version(
+        &self,
+        definition: Definition,
+        part: VersionPart,
+    ) -> Result<FileVersion, BusinessError> {
+        let file_item = self.get(definition.clone())?;
+        let version = file_item
+            .get_last_version()
+            .ok_or_else(|| BusinessError::VersionNotFound(definition.stem().to_string()))?;
+
+        Ok(version.increment(part)?)
+    }
+
+    /// `latest_with_prefix` resolves the highest registered version starting with the given
+    /// dotted prefix, e.g. `1.2` for the latest `1.2.x`.
+    pub(crate) fn latest_with_prefix(
+        &self,
+        definition: Definition,
+        prefix: &str,
+    ) -> Result<FileVersion, BusinessError> {
+        self.get(definition.clone())?
+            .latest_with_prefix(prefix)
+            .ok_or_else(|| {
+                BusinessError::VersionNotFound(format!("{} {}.x", definition.stem(), prefix))
+            })
+    }
+
+    /// Reads a version listed by the registry, a missing file means the entry is dangling.
+    fn read_version(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: FileLocation,
+    ) -> Result<String, BusinessError> {
+        self.processor
+            .read(definition.clone(), version.clone(), location)
+            .map_err(|err| match err {
+                BusinessError::DocumentError(DocumentError::NotFound(_)) => {
+                    BusinessError::DanglingEntry(format!("{} {}", definition.stem(), version))
+                }
+                err => err,
+            })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use super::*;
-    use mockall::{mock, predicate::eq};
+    use mockall::{
+        mock,
+        predicate::{eq, function},
+    };
 
     use crate::core::business::types::{Definition, Processor};
-    use crate::core::registry::types::{Registry, RegistryError};
+    use crate::core::registry::types::{
+        FileLayout, Registry, RegistryError, REGISTRY_VERSION_GENESIS,
+    };
 
     mock!(
         FakeRegistryProcessor{}
@@ -71,6 +557,8 @@ mod tests {
         impl RegistryProcessor for FakeRegistryProcessor {
             fn build(&self, path: PathBuf, registry: Registry) -> Result<(), RegistryError>;
             fn parse(&self, path: PathBuf) -> Result<Registry, RegistryError>;
+            fn inspect(&self, path: PathBuf) -> Result<RegistryInfo, RegistryError>;
+            fn remove(&self, path: &Path) -> Result<(), RegistryError>;
         }
     );
 
@@ -88,7 +576,20 @@ mod tests {
         FakeProcessor{}
 
         impl Processor for FakeProcessor {
-            fn define(&self, definition: Definition, version: FileVersion) -> Result<(), BusinessError>;
+            fn define(&self, definition: Definition, version: FileVersion, location: FileLocation, content: &str) -> Result<PathBuf, BusinessError>;
+            fn read(&self, definition: Definition, version: FileVersion, location: FileLocation) -> Result<String, BusinessError>;
+            fn export(&self, definition: Definition, version: FileVersion, location: FileLocation, target_dir: PathBuf) -> Result<(), BusinessError>;
+            fn scan(&self) -> Result<Vec<DefinitionFile>, BusinessError>;
+            fn exists(&self, definition: &Definition, version: &FileVersion, location: &FileLocation) -> bool;
+            fn path(&self, definition: &Definition, version: &FileVersion, location: &FileLocation) -> PathBuf;
+        }
+    );
+
+    mock!(
+        FakeLlmProvider{}
+
+        impl LlmProvider for FakeLlmProvider {
+            fn complete(&self, prompt: &str) -> Result<String, AiError>;
         }
     );
 
@@ -100,10 +601,16 @@ mod tests {
         #[test]
         fn test_define_new_business_def() {
             let mut processor = MockFakeProcessor::new();
+            processor.expect_exists().returning(|_, _, _| false);
             processor
                 .expect_define()
-                .with(eq(Definition::from("test_file")), eq(FileVersion::new()))
-                .returning(|_, _| Ok(()));
+                .with(
+                    eq(Definition::from("test_file")),
+                    eq(FileVersion::new()),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                    function(str::is_empty),
+                )
+                .returning(|_, _, _, _| Ok(PathBuf::from("output/test_file/0.1.0.md")));
 
             let expected_registry = Registry::new(Directory::from("output"));
             let mut registry_processor = MockFakeRegistryProcessor::new();
@@ -124,20 +631,91 @@ mod tests {
 
             let registry = RegistryManager::new(registry_processor, path_buf_wrapper);
             let manager = App::new(processor, registry);
-            let result = manager.define(Definition::from("test_file"), None);
-            assert!(result.is_ok())
+            let result =
+                manager.define(Definition::from("test_file"), None, FileLayout::Nested, "");
+            assert_eq!(
+                result.unwrap(),
+                DefineReport {
+                    version: FileVersion::new(),
+                    path: PathBuf::from("output/test_file/0.1.0.md"),
+                    added: true,
+                }
+            )
+        }
+
+        #[test]
+        fn test_define_in_output_dir() {
+            let location =
+                FileLocation::from(FileLayout::Nested).with_dir(Some(PathBuf::from("src/order")));
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_exists()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::new()),
+                    eq(location.clone()),
+                )
+                .returning(|_, _, _| false);
+            processor
+                .expect_define()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::new()),
+                    eq(location.clone()),
+                    function(str::is_empty),
+                )
+                .times(1)
+                .returning(|_, _, _, _| Ok(PathBuf::from("src/order/order/0.1.0.md")));
+
+            let mut registry_processor = MockFakeRegistryProcessor::new();
+            registry_processor
+                .expect_build()
+                .withf(|_, registry: &Registry| {
+                    registry
+                        .get_file(&FileName::from("order"))
+                        .is_some_and(|item| item.dir == Some(PathBuf::from("src/order")))
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+            registry_processor.expect_parse().returning(|_| {
+                Err(RegistryError::FsError(std::io::Error::from(
+                    std::io::ErrorKind::NotFound,
+                )))
+            });
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper.expect_exists().returning(|| true);
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(|| PathBuf::from("/tmp/ddai-missing-output"));
+            path_buf_wrapper
+                .expect_dir_name()
+                .returning(|| Some("output".to_string()));
+
+            let registry = RegistryManager::new(registry_processor, path_buf_wrapper);
+            let app = App::new(processor, registry);
+            let report = app
+                .define(Definition::from("order"), None, location, "")
+                .unwrap();
+            assert_eq!(report.version, FileVersion::from(REGISTRY_VERSION_GENESIS));
+            assert_eq!(report.path, PathBuf::from("src/order/order/0.1.0.md"));
+            assert!(report.added);
         }
 
         #[test]
         fn test_define_existed_business_def() {
             let mut processor = MockFakeProcessor::new();
+            processor.expect_exists().returning(|_, _, _| false);
             processor
                 .expect_define()
                 .with(
                     eq(Definition::from("test_file")),
                     eq(FileVersion::from("1.0.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                    function(|content: &str| content == "# Test file\n"),
                 )
-                .returning(|_, _| Ok(()));
+                .returning(|_, _, _, _| Ok(PathBuf::from("output/test_file/1.0.0.md")));
 
             let mut expected_file_item = FileItem::new(FileName::from("test_file"));
             expected_file_item.update(FileVersion::from("1.0.0"));
@@ -166,8 +744,769 @@ mod tests {
             let result = manager.define(
                 Definition::from("test_file"),
                 Some(FileVersion::from("1.0.0")),
+                FileLayout::Nested,
+                "# Test file\n",
+            );
+            assert!(result.is_ok())
+        }
+
+        #[test]
+        fn test_define_existing_file() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+            std::fs::write(temp_dir_path_buf.join("registry.json"), "{}").unwrap();
+
+            let build_app = |silent_duplicate: bool| {
+                let mut processor = MockFakeProcessor::new();
+                processor
+                    .expect_exists()
+                    .with(
+                        eq(Definition::from("order")),
+                        eq(FileVersion::from("1.0.0")),
+                        eq(FileLocation::from(FileLayout::Nested)),
+                    )
+                    .returning(|_, _, _| true);
+                processor.expect_define().never();
+                processor
+                    .expect_path()
+                    .times(usize::from(silent_duplicate))
+                    .returning(|_, _, _| PathBuf::from("output/order/1.0.0.md"));
+
+                let registry = Registry::new(Directory::from("output"));
+                let mut registry_processor = MockFakeRegistryProcessor::new();
+                registry_processor
+                    .expect_parse()
+                    .returning(move |_| Ok(registry.clone()));
+                registry_processor
+                    .expect_build()
+                    .times(usize::from(silent_duplicate))
+                    .returning(|_, _| Ok(()));
+
+                let temp_dir_path_buf = temp_dir_path_buf.clone();
+                let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+                path_buf_wrapper
+                    .expect_to_path_buf()
+                    .returning(move || temp_dir_path_buf.clone());
+
+                App::new(
+                    processor,
+                    RegistryManager::new(registry_processor, path_buf_wrapper),
+                )
+                .with_silent_duplicate(silent_duplicate)
+            };
+
+            let define = |app: App<_, _, _>| {
+                app.define(
+                    Definition::from("order"),
+                    Some(FileVersion::from("1.0.0")),
+                    FileLayout::Nested,
+                    "",
+                )
+            };
+
+            match define(build_app(false)) {
+                Err(BusinessError::AlreadyExists(msg)) => assert_eq!(msg, "order 1.0.0"),
+                _ => panic!("Expected AlreadyExists"),
+            }
+            assert_eq!(
+                define(build_app(true)).unwrap(),
+                DefineReport {
+                    version: FileVersion::from("1.0.0"),
+                    path: PathBuf::from("output/order/1.0.0.md"),
+                    added: true,
+                }
+            );
+        }
+
+        #[test]
+        fn test_define_keeps_registered_layout() {
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_exists().returning(|_, _, _| false);
+            processor
+                .expect_define()
+                .with(
+                    eq(Definition::from("test_file")),
+                    eq(FileVersion::from("1.0.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                    function(str::is_empty),
+                )
+                .returning(|_, _, _, _| Ok(PathBuf::from("output/test_file/1.0.0.md")));
+
+            let mut expected_registry = Registry::new(Directory::from("output"));
+            expected_registry.add_file(FileItem::new(FileName::from("test_file")));
+
+            let mut registry_processor = MockFakeRegistryProcessor::new();
+            registry_processor.expect_build().returning(|_, _| Ok(()));
+            registry_processor
+                .expect_parse()
+                .returning(move |_| Ok(expected_registry.clone()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper.expect_exists().returning(|| true);
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(|| PathBuf::from("/tmp/output"));
+            path_buf_wrapper
+                .expect_dir_name()
+                .returning(|| Some("output".to_string()));
+
+            let registry = RegistryManager::new(registry_processor, path_buf_wrapper);
+            let manager = App::new(processor, registry);
+            let result = manager.define(
+                Definition::from("test_file"),
+                Some(FileVersion::from("1.0.0")),
+                FileLayout::Flat,
+                "",
             );
             assert!(result.is_ok())
         }
     }
+
+    fn build_registry_manager(
+        registry: Registry,
+    ) -> RegistryManager<MockFakeRegistryProcessor, MockFakePathBufWrapper> {
+        let mut registry_processor = MockFakeRegistryProcessor::new();
+        registry_processor
+            .expect_parse()
+            .returning(move |_| Ok(registry.clone()));
+
+        let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+        path_buf_wrapper
+            .expect_to_path_buf()
+            .returning(|| PathBuf::from("/tmp/output"));
+
+        RegistryManager::new(registry_processor, path_buf_wrapper)
+    }
+
+    mod test_export {
+        use super::*;
+
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+
+        #[test]
+        fn test_export_registry_reports_dangling_entries() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+            std::fs::write(temp_dir_path_buf.join("registry.json"), "{}").unwrap();
+
+            let mut file_item = FileItem::from_version(FileName::from("order"), "1.0.0".into());
+            file_item.update(FileVersion::from("1.1.0"));
+            let registry = Registry::from_files(Directory::from("businesses"), vec![file_item]);
+            let expected_registry = registry.clone();
+
+            let mut registry_processor = MockFakeRegistryProcessor::new();
+            registry_processor
+                .expect_parse()
+                .returning(move |_| Ok(registry.clone()));
+            registry_processor
+                .expect_build()
+                .with(
+                    eq(PathBuf::from("/tmp/backup/registry.json")),
+                    eq(expected_registry),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_export()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::from("1.0.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                    eq(PathBuf::from("/tmp/backup")),
+                )
+                .returning(|_, _, _, _| Ok(()));
+            processor
+                .expect_export()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::from("1.1.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                    eq(PathBuf::from("/tmp/backup")),
+                )
+                .returning(|_, _, _, _| {
+                    Err(DocumentError::NotFound("order/1.1.0.md".to_string()).into())
+                });
+
+            let app = App::new(
+                processor,
+                RegistryManager::new(registry_processor, path_buf_wrapper),
+            );
+            let report = app.export_registry(PathBuf::from("/tmp/backup")).unwrap();
+            assert_eq!(report.exported, 1);
+            assert_eq!(report.dangling, vec!["order 1.1.0".to_string()]);
+        }
+    }
+
+    mod test_orphans {
+        use super::*;
+
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+
+        #[test]
+        fn test_orphan_gets_registered() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+            std::fs::write(temp_dir_path_buf.join("registry.json"), "{}").unwrap();
+
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![FileItem::from_version(
+                    FileName::from("order"),
+                    "1.0.0".into(),
+                )],
+            );
+
+            let mut registry_processor = MockFakeRegistryProcessor::new();
+            registry_processor
+                .expect_parse()
+                .returning(move |_| Ok(registry.clone()));
+            registry_processor
+                .expect_build()
+                .withf(|_, registry: &Registry| {
+                    registry
+                        .get_file(&FileName::from("order"))
+                        .is_some_and(|item| item.versions.contains(&"1.1.0".into()))
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_scan().returning(|| {
+                Ok(["1.0.0", "1.1.0"]
+                    .into_iter()
+                    .map(|version| DefinitionFile {
+                        definition: Definition::from("order"),
+                        version: FileVersion::from(version),
+                        layout: FileLayout::Nested,
+                    })
+                    .collect())
+            });
+
+            let app = App::new(
+                processor,
+                RegistryManager::new(registry_processor, path_buf_wrapper),
+            );
+            let orphans = app.orphans().unwrap();
+            assert_eq!(orphans.len(), 1);
+            assert_eq!(orphans[0].version, FileVersion::from("1.1.0"));
+
+            assert!(app.register(&orphans[0]).unwrap());
+
+            let flat = DefinitionFile {
+                layout: FileLayout::Flat,
+                ..orphans[0].clone()
+            };
+            assert!(matches!(
+                app.register(&flat),
+                Err(BusinessError::InvalidDefinition(_))
+            ));
+        }
+    }
+
+    mod test_lint {
+        use super::*;
+
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+
+        #[test]
+        fn test_lint_reports_missing_sections_per_version() {
+            let mut file_item = FileItem::new(FileName::from("order"));
+            file_item.update(FileVersion::from("1.0.0"));
+
+            let registry = Registry::from_files(Directory::from("output"), vec![file_item]);
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_read()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::new()),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                )
+                .returning(|_, _, _| Ok("## Ubiquitous Language\n".to_string()));
+            processor
+                .expect_read()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::from("1.0.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                )
+                .returning(|_, _, _| {
+                    Ok("## Ubiquitous Language\nOrder\n## Bounded Contexts\nSales\n".to_string())
+                });
+
+            let app = App::new(processor, build_registry_manager(registry));
+            let required = vec![
+                "Ubiquitous Language".to_string(),
+                "Bounded Contexts".to_string(),
+            ];
+
+            let reports = app.lint(Definition::from("order"), &required).unwrap();
+            assert_eq!(reports.len(), 2);
+            assert_eq!(reports[0].version, FileVersion::new());
+            assert_eq!(reports[0].missing, required);
+            assert!(reports[1].is_ok());
+        }
+
+        #[test]
+        fn test_lint_unknown_definition() {
+            let registry = Registry::new(Directory::from("output"));
+            let processor = MockFakeProcessor::new();
+
+            let app = App::new(processor, build_registry_manager(registry));
+            let result = app.lint(Definition::from("order"), &[]);
+            assert!(matches!(result, Err(BusinessError::DefinitionNotFound(_))));
+        }
+    }
+
+    mod test_registry_problems {
+        use super::*;
+
+        use crate::core::registry::memory::{MemoryDir, MemoryProcessor};
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+        use crate::core::types::CoreError;
+
+        #[test]
+        fn test_registry_problems_reports_invalid_version() {
+            let mut order =
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.0.0"));
+            order.update(FileVersion::from("1..0"));
+            let invoice =
+                FileItem::from_version(FileName::from("invoice"), FileVersion::from("0.1.0"));
+            let registry = Registry::from_files(Directory::from("output"), vec![order, invoice]);
+
+            // only the registry content is validated, no business file is looked at
+            let registry_processor =
+                MemoryProcessor::new().with_registry("/memory/output/registry.json", registry);
+            let app = App::new(
+                MockFakeProcessor::new(),
+                RegistryManager::new(registry_processor, MemoryDir::new("/memory/output")),
+            );
+            let problems = app.registry_problems().unwrap();
+            assert_eq!(problems.len(), 1);
+            assert_eq!(problems[0].location, "order 1..0");
+            assert!(matches!(
+                &problems[0].error,
+                CoreError::ValidationError { field: Some(field), .. } if field == "version"
+            ));
+        }
+    }
+
+    mod test_read {
+        use super::*;
+
+        use crate::core::registry::memory::{MemoryDir, MemoryProcessor};
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+
+        fn build_registry() -> Registry {
+            let mut file_item = FileItem::new(FileName::from("order"));
+            file_item.update(FileVersion::from("1.0.0"));
+
+            Registry::from_files(Directory::from("output"), vec![file_item])
+        }
+
+        #[test]
+        fn test_read_defaults_to_last_version() {
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_read()
+                .with(
+                    eq(Definition::from("order")),
+                    eq(FileVersion::from("1.0.0")),
+                    eq(FileLocation::from(FileLayout::Nested)),
+                )
+                .returning(|_, _, _| Ok("# Order".to_string()));
+
+            let app = App::new(processor, build_registry_manager(build_registry()));
+            let (version, content) = app.read(Definition::from("order"), None).unwrap();
+            assert_eq!(version, FileVersion::from("1.0.0"));
+            assert_eq!(content, "# Order");
+        }
+
+        #[test]
+        fn test_versions_are_sorted() {
+            let mut file_item =
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.10.0"));
+            for version in ["1.2.0", "1.9.0"] {
+                file_item.update(FileVersion::from(version));
+            }
+            let registry = Registry::from_files(Directory::from("output"), vec![file_item]);
+
+            let app = App::new(MockFakeProcessor::new(), build_registry_manager(registry));
+            let versions = app.versions(Definition::from("order")).unwrap();
+            assert_eq!(
+                versions,
+                vec![
+                    FileVersion::from("1.2.0"),
+                    FileVersion::from("1.9.0"),
+                    FileVersion::from("1.10.0"),
+                ]
+            );
+
+            let missing = app.versions(Definition::from("billing"));
+            assert!(matches!(missing, Err(BusinessError::DefinitionNotFound(_))));
+        }
+
+        #[test]
+        fn test_resolve_version_defaults_to_newest() {
+            let mut file_item =
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.10.0"));
+            file_item.update(FileVersion::from("1.2.0"));
+            let registry = Registry::from_files(Directory::from("output"), vec![file_item]);
+
+            let app = App::new(MockFakeProcessor::new(), build_registry_manager(registry));
+            let version = app.resolve_version(Definition::from("order"), None);
+            assert_eq!(version.unwrap(), FileVersion::from("1.10.0"));
+
+            let version =
+                app.resolve_version(Definition::from("order"), Some(FileVersion::from("1.2.0")));
+            assert_eq!(version.unwrap(), FileVersion::from("1.2.0"));
+
+            let missing =
+                app.resolve_version(Definition::from("order"), Some(FileVersion::from("2.0.0")));
+            assert!(matches!(missing, Err(BusinessError::VersionNotFound(_))));
+        }
+
+        #[test]
+        fn test_resolve_version_without_versions() {
+            let mut file_item = FileItem::new(FileName::from("order"));
+            file_item.versions.clear();
+            let registry = Registry::from_files(Directory::from("output"), vec![file_item]);
+
+            let app = App::new(MockFakeProcessor::new(), build_registry_manager(registry));
+            let err = app
+                .resolve_version(Definition::from("order"), None)
+                .unwrap_err();
+            assert!(matches!(err, BusinessError::VersionNotFound(_)));
+            assert!(err.to_string().contains("order has no registered versions"));
+        }
+
+        #[test]
+        fn test_list_domain() {
+            let mut invoice = FileItem::new(FileName::from("invoice"));
+            invoice.domain = Some("billing".to_string());
+            let registry = Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::new(FileName::from("order")), invoice],
+            );
+
+            let registry_processor =
+                MemoryProcessor::new().with_registry("/memory/output/registry.json", registry);
+            let app = App::new(
+                MockFakeProcessor::new(),
+                RegistryManager::new(registry_processor, MemoryDir::new("/memory/output")),
+            );
+
+            // ungrouped definitions are listed as before
+            let files = app.list(None).unwrap();
+            assert_eq!(files.len(), 2);
+            assert!(files[0].domain.is_none());
+
+            let files = app.list(Some("billing")).unwrap();
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].name, FileName::from("invoice"));
+            assert!(app.list(Some("shipping")).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_list_without_registry_is_empty() {
+            let processor = MockFakeProcessor::new();
+            let registry_processor = MockFakeRegistryProcessor::new();
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(|| PathBuf::from("/tmp/ddai-missing-output"));
+            path_buf_wrapper
+                .expect_dir_name()
+                .returning(|| Some("output".to_string()));
+
+            let registry = RegistryManager::new(registry_processor, path_buf_wrapper);
+            let app = App::new(processor, registry);
+            assert!(app.list(None).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_latest_with_prefix() {
+            let processor = MockFakeProcessor::new();
+
+            let app = App::new(processor, build_registry_manager(build_registry()));
+            let version = app.latest_with_prefix(Definition::from("order"), "1");
+            assert_eq!(version.unwrap(), FileVersion::from("1.0.0"));
+
+            let result = app.latest_with_prefix(Definition::from("order"), "1.1");
+            assert!(matches!(result, Err(BusinessError::VersionNotFound(_))));
+        }
+
+        #[test]
+        fn test_next_version() {
+            let processor = MockFakeProcessor::new();
+
+            let app = App::new(processor, build_registry_manager(build_registry()));
+            let version = app.next_version(Definition::from("order"), VersionPart::Minor);
+            assert_eq!(version.unwrap(), FileVersion::from("1.1.0"));
+
+            let result = app.next_version(Definition::from("billing"), VersionPart::Patch);
+            assert!(matches!(result, Err(BusinessError::DefinitionNotFound(_))));
+        }
+
+        #[test]
+        fn test_read_unregistered_version() {
+            let processor = MockFakeProcessor::new();
+
+            let app = App::new(processor, build_registry_manager(build_registry()));
+            let result = app.read(Definition::from("order"), Some(FileVersion::from("2.0.0")));
+            assert!(matches!(result, Err(BusinessError::VersionNotFound(_))));
+        }
+
+        #[test]
+        fn test_read_dangling_entry() {
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_read().returning(|_, _, _| {
+                Err(BusinessError::DocumentError(DocumentError::NotFound(
+                    "order/0.1.0.md".to_string(),
+                )))
+            });
+
+            let app = App::new(processor, build_registry_manager(build_registry()));
+            let result = app.read(Definition::from("order"), Some(FileVersion::new()));
+            assert!(matches!(result, Err(BusinessError::DanglingEntry(_))));
+        }
+    }
+
+    mod test_analyze {
+        use super::*;
+
+        use crate::core::business::types::Language;
+        use crate::core::registry::types::{Directory, FileItem, FileName};
+
+        const CONTENT: &str = "## Ubiquitous Language\nOrder\n";
+
+        fn build_app(
+            files: Vec<FileItem>,
+        ) -> App<MockFakeProcessor, MockFakeRegistryProcessor, MockFakePathBufWrapper> {
+            let registry = Registry::from_files(Directory::from("output"), files);
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_read()
+                .returning(|_, _, _| Ok(CONTENT.to_string()));
+
+            App::new(processor, build_registry_manager(registry))
+        }
+
+        fn build_params() -> AnalyzeParameters {
+            AnalyzeParameters::new(
+                Definition::from("order"),
+                FileVersion::new(),
+                Some(Language::from("Rust")),
+                None,
+            )
+        }
+
+        #[test]
+        fn test_analyze_returns_model_output() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .withf(|prompt: &str| {
+                    prompt.contains("Programming language: Rust") && prompt.ends_with(CONTENT)
+                })
+                .times(1)
+                .returning(|_| Ok("# Architecture".to_string()));
+
+            let app =
+                build_app(vec![FileItem::new(FileName::from("order"))]).with_provider(provider);
+            let output = app.analyze(build_params()).unwrap();
+            assert_eq!(output, "# Architecture");
+        }
+
+        #[test]
+        fn test_analyze_without_provider() {
+            let app = build_app(vec![FileItem::new(FileName::from("order"))]);
+            let result = app.analyze(build_params());
+            assert!(matches!(
+                result,
+                Err(BusinessError::AiError(AiError::NotConfigured))
+            ));
+        }
+
+        #[test]
+        fn test_analyze_chunked() {
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_read().returning(|_, _, _| {
+                Ok("## Ubiquitous Language\nOrder\n## Aggregates\nCart\n".to_string())
+            });
+            let registry = Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::new(FileName::from("order"))],
+            );
+
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .withf(|prompt: &str| {
+                    prompt.contains("Programming language: Rust")
+                        && prompt.contains("(part 1 of 2")
+                        && prompt.ends_with("## Ubiquitous Language\nOrder\n")
+                })
+                .times(1)
+                .returning(|_| Ok("# Language".to_string()));
+            provider
+                .expect_complete()
+                .withf(|prompt: &str| {
+                    prompt.contains("(part 2 of 2") && prompt.ends_with("## Aggregates\nCart\n")
+                })
+                .times(1)
+                .returning(|_| Ok("# Aggregates".to_string()));
+
+            let app = App::new(processor, build_registry_manager(registry)).with_provider(provider);
+            let analysis = app.analyze_chunked(build_params(), 30).unwrap();
+            assert_eq!(
+                analysis,
+                ChunkedAnalysis {
+                    output: "# Language\n\n# Aggregates".to_string(),
+                    chunks: 2,
+                }
+            );
+        }
+
+        #[test]
+        fn test_analyze_invalid_parameters() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider.expect_complete().never();
+
+            let app =
+                build_app(vec![FileItem::new(FileName::from("order"))]).with_provider(provider);
+            let params = build_params().with_additional_prompt(String::new());
+            let result = app.analyze(params);
+            assert!(matches!(result, Err(BusinessError::CoreError(_))));
+        }
+
+        #[test]
+        fn test_analyze_unregistered_version() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider.expect_complete().never();
+
+            let app =
+                build_app(vec![FileItem::new(FileName::from("order"))]).with_provider(provider);
+            let params = AnalyzeParameters::new(
+                Definition::from("order"),
+                "9.9.9".into(),
+                Some(Language::from("Rust")),
+                None,
+            );
+            let result = app.analyze(params);
+            assert!(matches!(result, Err(BusinessError::VersionNotFound(_))));
+        }
+
+        #[test]
+        fn test_analyze_provider_failure() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider
+                .expect_complete()
+                .returning(|_| Err(AiError::RequestFailed("timeout".to_string())));
+
+            let app =
+                build_app(vec![FileItem::new(FileName::from("order"))]).with_provider(provider);
+            let result = app.analyze(build_params());
+            assert!(matches!(
+                result,
+                Err(BusinessError::AiError(AiError::RequestFailed(_)))
+            ));
+        }
+
+        #[test]
+        fn test_analyze_content_filtered() {
+            let mut provider = MockFakeLlmProvider::new();
+            provider.expect_complete().times(1).returning(|_| {
+                Err(AiError::ContentFiltered(
+                    "the prompt was flagged by the safety system".to_string(),
+                ))
+            });
+
+            let app =
+                build_app(vec![FileItem::new(FileName::from("order"))]).with_provider(provider);
+            match app.analyze(build_params()) {
+                Err(BusinessError::AiError(AiError::ContentFiltered(reason))) => {
+                    assert_eq!(reason, "the prompt was flagged by the safety system")
+                }
+                _ => panic!("Expected ContentFiltered"),
+            }
+        }
+
+        #[test]
+        fn test_estimate_prices_the_prompt() {
+            let params = build_params();
+            let prompt = params.to_prompt(CONTENT);
+
+            let app = build_app(vec![FileItem::new(FileName::from("order"))]);
+            let estimate = app.estimate(params, Some(2.0)).unwrap();
+            assert_eq!(estimate, PromptEstimate::new(&prompt).with_price(2.0));
+        }
+
+        #[test]
+        fn test_estimate_includes_registry() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+            std::fs::write(temp_dir_path_buf.join("registry.json"), "{}").unwrap();
+
+            let registry = Registry::from_files(
+                Directory::from("output"),
+                vec![
+                    FileItem::new(FileName::from("order")),
+                    FileItem::from_version(FileName::from("billing"), "2.0.0".into()),
+                ],
+            );
+            let summary = registry.summary(MAX_REGISTRY_SUMMARY_CHARS);
+            assert_eq!(summary, "order 0.1.0\nbilling 2.0.0");
+
+            let mut registry_processor = MockFakeRegistryProcessor::new();
+            registry_processor
+                .expect_parse()
+                .returning(move |_| Ok(registry.clone()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_read()
+                .returning(|_, _, _| Ok(CONTENT.to_string()));
+
+            let app = App::new(
+                processor,
+                RegistryManager::new(registry_processor, path_buf_wrapper),
+            );
+            let params = build_params().with_include_registry(true);
+            let prompt = params.to_prompt_with_registry(CONTENT, Some(&summary));
+            assert!(prompt.contains("with their latest version:\norder 0.1.0\nbilling 2.0.0"));
+            assert_eq!(
+                app.estimate(params, None).unwrap(),
+                PromptEstimate::new(&prompt)
+            );
+        }
+
+        #[test]
+        fn test_estimate_invalid_parameters() {
+            let app = build_app(vec![FileItem::new(FileName::from("order"))]);
+            let params = build_params().with_additional_prompt(String::new());
+            let result = app.estimate(params, None);
+            assert!(matches!(result, Err(BusinessError::CoreError(_))));
+        }
+    }
 }
@@ -0,0 +1,280 @@
+use tracing::warn;
+
+/// Sections every business definition is expected to carry when no other set is configured.
+pub const DEFAULT_REQUIRED_SECTIONS: [&str; 2] = ["Ubiquitous Language", "Bounded Contexts"];
+
+const SECTION_LEVEL: usize = 2;
+const CODE_FENCE: &str = "```";
+
+/// `Section` is a level-two markdown heading (`## Title`) and the content below it.
+///
+/// The content runs until the next heading of the same or a higher level, so nested
+/// headings (`### ...`) are part of their parent section.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Section {
+    pub(crate) title: String,
+    pub(crate) body: String,
+}
+
+impl Section {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.body.trim().is_empty()
+    }
+}
+
+/// Returns the heading level and title when the line is an ATX heading (`# Title`).
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// Normalizes a configured section name, so both `Bounded Contexts` and
+/// `## Bounded Contexts` refer to the same section.
+fn normalize_title(title: &str) -> &str {
+    title.trim().trim_start_matches('#').trim()
+}
+
+/// `Heading` is a section boundary of a markdown document, a heading of level one or two.
+struct Heading<'a> {
+    level: usize,
+    title: &'a str,
+
+    /// Byte offset of the heading line
+    start: usize,
+
+    /// Byte offset right after the heading line
+    end: usize,
+}
+
+/// Finds the section boundaries of a markdown document, in order. Headings inside fenced
+/// code blocks are ignored.
+fn section_headings(content: &str) -> Vec<Heading<'_>> {
+    let mut headings = Vec::new();
+    let mut in_code_block = false;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        if line.trim_start().starts_with(CODE_FENCE) {
+            in_code_block = !in_code_block;
+        }
+
+        if in_code_block {
+            continue;
+        }
+
+        match parse_heading(line.trim_end_matches(['\n', '\r'])) {
+            Some((level, title)) if level <= SECTION_LEVEL => headings.push(Heading {
+                level,
+                title,
+                start,
+                end: offset,
+            }),
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// `scan_sections` is a lightweight scanner collecting every level-two section of a
+/// markdown document, in order. Headings inside fenced code blocks are ignored.
+pub(crate) fn scan_sections(content: &str) -> Vec<Section> {
+    let headings = section_headings(content);
+
+    headings
+        .iter()
+        .enumerate()
+        .filter(|(_, heading)| heading.level == SECTION_LEVEL)
+        .map(|(index, heading)| {
+            let end = headings
+                .get(index + 1)
+                .map_or(content.len(), |next| next.start);
+            Section {
+                title: heading.title.to_string(),
+                body: content[heading.end..end].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// `split_sections` splits a markdown document into chunks of at most `max_chars`
+/// characters, cutting only at section boundaries and keeping as many whole sections
+/// together as fit.
+///
+/// A single section larger than `max_chars` is cut every `max_chars` characters instead,
+/// with a warning since its parts lose their context.
+pub(crate) fn split_sections(content: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut bounds: Vec<usize> = section_headings(content)
+        .iter()
+        .map(|heading| heading.start)
+        .collect();
+    bounds.push(content.len());
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for end in bounds {
+        let part = &content[start..end];
+        start = end;
+        if part.is_empty() {
+            continue;
+        }
+
+        if current.chars().count() + part.chars().count() <= max_chars {
+            current.push_str(part);
+            continue;
+        }
+
+        if !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        if part.chars().count() <= max_chars {
+            current.push_str(part);
+            continue;
+        }
+
+        let title = part.lines().next().unwrap_or_default().trim();
+        warn!(
+            "Section {:?} is larger than {} characters, splitting it without regard to its structure",
+            title, max_chars
+        );
+        let chars: Vec<char> = part.chars().collect();
+        chunks.extend(chars.chunks(max_chars).map(|chunk| chunk.iter().collect()));
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// `missing_sections` returns the required sections that are either absent from the
+/// document or present without any content. Titles are compared case-insensitively.
+pub(crate) fn missing_sections(content: &str, required: &[String]) -> Vec<String> {
+    let sections = scan_sections(content);
+
+    required
+        .iter()
+        .map(|title| normalize_title(title))
+        .filter(|title| {
+            !sections
+                .iter()
+                .any(|section| section.title.eq_ignore_ascii_case(title) && !section.is_empty())
+        })
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "# Order Service
+
+## Ubiquitous Language
+
+- Order: a customer's request to buy products
+
+## Bounded Contexts
+
+### Sales
+
+Handles orders.
+
+```md
+## Not A Section
+```
+
+## Aggregates
+
+";
+
+    fn required(titles: &[&str]) -> Vec<String> {
+        titles.iter().map(|title| title.to_string()).collect()
+    }
+
+    #[test]
+    fn test_scan_sections() {
+        let sections = scan_sections(DOCUMENT);
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Ubiquitous Language", "Bounded Contexts", "Aggregates"]
+        );
+
+        assert!(sections[1].body.contains("### Sales"));
+        assert!(sections[1].body.contains("## Not A Section"));
+        assert!(sections[2].is_empty());
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let chunks = split_sections(DOCUMENT, 80);
+        assert_eq!(chunks.concat(), DOCUMENT);
+        assert_eq!(
+            chunks,
+            vec![
+                "# Order Service\n\n".to_string(),
+                "## Ubiquitous Language\n\n- Order: a customer's request to buy products\n\n"
+                    .to_string(),
+                "## Bounded Contexts\n\n### Sales\n\nHandles orders.\n\n```md\n## Not A Section\n```\n\n"
+                    .to_string(),
+                "## Aggregates\n\n".to_string(),
+            ]
+        );
+
+        // small sections share a chunk, everything fits in a single one
+        assert_eq!(split_sections(DOCUMENT, 100).len(), 2);
+        assert_eq!(split_sections(DOCUMENT, 1_000), vec![DOCUMENT.to_string()]);
+    }
+
+    #[test]
+    fn test_split_oversized_section() {
+        let content = "## Glossary\néééééééééé\n## Events\n";
+        let chunks = split_sections(content, 12);
+        assert_eq!(chunks.concat(), content);
+        assert_eq!(
+            chunks,
+            vec![
+                "## Glossary\n".to_string(),
+                "éééééééééé\n".to_string(),
+                "## Events\n".to_string(),
+            ]
+        );
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 12));
+    }
+
+    #[test]
+    fn test_missing_sections_all_present() {
+        let missing = missing_sections(
+            DOCUMENT,
+            &required(&["Ubiquitous Language", "## bounded contexts"]),
+        );
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_missing_sections_absent_or_empty() {
+        let missing = missing_sections(
+            DOCUMENT,
+            &required(&["Ubiquitous Language", "Aggregates", "Domain Events"]),
+        );
+        assert_eq!(missing, required(&["Aggregates", "Domain Events"]));
+    }
+}
@@ -1,2 +1,4 @@
 pub(crate) mod app;
+pub(crate) mod markdown;
+pub(crate) mod template;
 pub(crate) mod types;
@@ -0,0 +1,84 @@
+use crate::core::business::types::BusinessError;
+
+const PLACEHOLDER_OPEN: &str = "{{";
+const PLACEHOLDER_CLOSE: &str = "}}";
+
+/// `render_template` substitutes every `{{key}}` placeholder of a definition template with
+/// the value of its field, spaces inside the braces being ignored.
+///
+/// The template is rejected as a whole when it uses a placeholder without a field, so a
+/// definition is never written with placeholders left in it.
+pub(crate) fn render_template(
+    template: &str,
+    fields: &[(&str, &str)],
+) -> Result<String, BusinessError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unresolved: Vec<&str> = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let Some(end) = after_open.find(PLACEHOLDER_CLOSE) else {
+            break;
+        };
+
+        rendered.push_str(&rest[..start]);
+        let key = after_open[..end].trim();
+        match fields.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) => rendered.push_str(value),
+            None => {
+                if !unresolved.contains(&key) {
+                    unresolved.push(key);
+                }
+            }
+        }
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+    rendered.push_str(rest);
+
+    if !unresolved.is_empty() {
+        let names = |keys: &mut dyn Iterator<Item = &str>| {
+            keys.map(|key| format!("{{{{{}}}}}", key))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        return Err(BusinessError::TemplateError(format!(
+            "unresolved placeholders {}, the supported ones are {}",
+            names(&mut unresolved.into_iter()),
+            names(&mut fields.iter().map(|(name, _)| *name))
+        )));
+    }
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: [(&str, &str); 2] = [("name", "order"), ("version", "1.0.0")];
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "# {{name}}\n\nVersion {{ version }} of {{name}}.\n",
+            &FIELDS,
+        )
+        .unwrap();
+        assert_eq!(rendered, "# order\n\nVersion 1.0.0 of order.\n");
+
+        // braces without a closing pair are plain text
+        assert_eq!(render_template("{{name", &FIELDS).unwrap(), "{{name");
+    }
+
+    #[test]
+    fn test_render_template_unresolved() {
+        match render_template("{{name}} by {{owner}}, {{owner}} and {{team}}", &FIELDS) {
+            Err(BusinessError::TemplateError(msg)) => assert_eq!(
+                msg,
+                "unresolved placeholders {{owner}}, {{team}}, the supported ones are {{name}}, {{version}}"
+            ),
+            _ => panic!("Expected TemplateError"),
+        }
+    }
+}
@@ -1,10 +1,20 @@
 use std::io::Error as IoError;
+use std::path::PathBuf;
 use thiserror::Error;
+use tracing::warn;
 
-use crate::core::registry::types::{FileName, FileVersion, RegistryError};
-use crate::core::types::{CoreError, Validator};
+use crate::core::ai::types::AiError;
+use crate::core::document::types::DocumentError;
+use crate::core::project::types::ProjectError;
+use crate::core::registry::types::{
+    FileLayout, FileLocation, FileName, FileVersion, RegistryError,
+};
+use crate::core::types::{impl_non_empty_validator, validate_all, CoreError, Validator};
 
-pub const BUSINESS_DIR_NAME: &str = "./businesses";
+pub const BUSINESS_FILE_EXTENSION: &str = ".md";
+
+/// Default maximum length of a [`Definition`] in bytes, the file name limit of most filesystems.
+pub const MAX_DEFINITION_LENGTH: usize = 255;
 
 #[derive(Debug, Error)]
 pub(crate) enum BusinessError {
@@ -19,6 +29,45 @@ pub(crate) enum BusinessError {
 
     #[error("[business error] registry error: {0}")]
     RegistryError(#[from] RegistryError),
+
+    #[error("[business error] document error: {0}")]
+    DocumentError(#[from] DocumentError),
+
+    #[error("[business error] business definition not found: {0}")]
+    DefinitionNotFound(String),
+
+    #[error("[business error] project error: {0}")]
+    ProjectError(#[from] ProjectError),
+
+    #[error("[business error] business definition version not found: {0}")]
+    VersionNotFound(String),
+
+    #[error("[business error] registry lists {0} but its file is missing (dangling entry), restore the file or define the version again")]
+    DanglingEntry(String),
+
+    #[error("[business error] lint failed: {0}")]
+    LintFailed(String),
+
+    #[error("[business error] invalid registry: {0}")]
+    InvalidRegistry(String),
+
+    #[error("[business error] ai error: {0}")]
+    AiError(#[from] AiError),
+
+    #[error("[business error] no business definitions are registered")]
+    NoDefinitions,
+
+    #[error("[business error] business definition version already exists: {0}, use --silent-duplicate to accept it")]
+    AlreadyExists(String),
+
+    #[error("[business error] on-change hook failed: {0}")]
+    HookFailed(String),
+
+    #[error("[business error] interactive prompt failed: {0}")]
+    PromptFailed(String),
+
+    #[error("[business error] template error: {0}")]
+    TemplateError(String),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -29,8 +78,19 @@ impl Definition {
         &self.0
     }
 
+    /// Returns the definition name without a trailing [`BUSINESS_FILE_EXTENSION`].
+    ///
+    /// Definition files are named after the stem, either as a directory holding `{version}.md`
+    /// or as `{stem}-{version}.md`, so `foo.md` and `foo` must resolve to the same files.
+    pub(crate) fn stem(&self) -> &str {
+        self.0
+            .strip_suffix(BUSINESS_FILE_EXTENSION)
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or(&self.0)
+    }
+
     pub(crate) fn to_filename(&self) -> FileName {
-        FileName::from(self.as_str())
+        FileName::from(self.stem())
     }
 }
 
@@ -46,11 +106,55 @@ impl From<&str> for Definition {
     }
 }
 
-impl Validator for Definition {
-    fn validate(&self) -> Result<(), CoreError> {
+impl From<FileName> for Definition {
+    fn from(name: FileName) -> Self {
+        Definition::from(name.as_str())
+    }
+}
+
+impl From<&FileName> for Definition {
+    fn from(name: &FileName) -> Self {
+        Definition::from(name.as_str())
+    }
+}
+
+impl From<Definition> for FileName {
+    fn from(definition: Definition) -> Self {
+        definition.to_filename()
+    }
+}
+
+impl From<&Definition> for FileName {
+    fn from(definition: &Definition) -> Self {
+        definition.to_filename()
+    }
+}
+
+impl Definition {
+    /// `validate_with_max_len` validates the definition, allowing at most `max_len` bytes.
+    ///
+    /// A definition ends up as a directory or file name, so it is checked against the usual
+    /// filesystem limits here rather than failing later with an opaque OS error.
+    pub(crate) fn validate_with_max_len(&self, max_len: usize) -> Result<(), CoreError> {
         if self.0.is_empty() {
-            return Err(CoreError::ValidationError(
-                "Definition cannot be empty".to_string(),
+            return Err(CoreError::field("name", "Definition cannot be empty"));
+        }
+
+        if self.0.len() > max_len {
+            return Err(CoreError::field(
+                "name",
+                format!(
+                    "Definition is {} bytes long, the maximum is {} bytes",
+                    self.0.len(),
+                    max_len
+                ),
+            ));
+        }
+
+        if self.0.chars().any(char::is_control) {
+            return Err(CoreError::field(
+                "name",
+                "Definition cannot contain control characters",
             ));
         }
 
@@ -58,9 +162,21 @@ impl Validator for Definition {
     }
 }
 
+impl Validator for Definition {
+    fn validate(&self) -> Result<(), CoreError> {
+        self.validate_with_max_len(MAX_DEFINITION_LENGTH)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct Language(String);
 
+impl Language {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<String> for Language {
     fn from(lang: String) -> Self {
         Language(lang)
@@ -73,21 +189,17 @@ impl From<&str> for Language {
     }
 }
 
-impl Validator for Language {
-    fn validate(&self) -> Result<(), CoreError> {
-        if self.0.is_empty() {
-            return Err(CoreError::ValidationError(
-                "Language cannot be empty".to_string(),
-            ));
-        }
-
-        Ok(())
-    }
-}
+impl_non_empty_validator!(Language, "language", "Language cannot be empty");
 
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct Architecture(String);
 
+impl Architecture {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<String> for Architecture {
     fn from(arch: String) -> Self {
         Architecture(arch)
@@ -100,17 +212,7 @@ impl From<&str> for Architecture {
     }
 }
 
-impl Validator for Architecture {
-    fn validate(&self) -> Result<(), CoreError> {
-        if self.0.is_empty() {
-            return Err(CoreError::ValidationError(
-                "Architecture cannot be empty".to_string(),
-            ));
-        }
-
-        Ok(())
-    }
-}
+impl_non_empty_validator!(Architecture, "architect", "Architecture cannot be empty");
 
 pub(crate) struct AdditionalPrompt(String);
 
@@ -126,18 +228,60 @@ impl From<&str> for AdditionalPrompt {
     }
 }
 
-impl Validator for AdditionalPrompt {
-    fn validate(&self) -> Result<(), CoreError> {
-        if self.0.is_empty() {
-            return Err(CoreError::ValidationError(
-                "Additional prompt cannot be empty".to_string(),
-            ));
-        }
+impl_non_empty_validator!(
+    AdditionalPrompt,
+    "additional_prompt",
+    "Additional prompt cannot be empty"
+);
+
+/// `AnalyzeMode` selects the prompt template used to analyze a business definition.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum AnalyzeMode {
+    /// Design the technical architecture of the definition
+    #[default]
+    Generate,
+
+    /// Critique the definition itself, pointing out its gaps
+    Review,
+}
 
-        Ok(())
-    }
+/// Maximum size in characters of the registry summary added to an analyze prompt.
+pub(crate) const MAX_REGISTRY_SUMMARY_CHARS: usize = 4000;
+
+/// `CompatibilityRule` flags a language and architecture pairing that rarely makes sense.
+///
+/// Both names are matched case-insensitively against the analyze parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CompatibilityRule {
+    pub(crate) language: &'static str,
+    pub(crate) architecture: &'static str,
+    pub(crate) reason: &'static str,
 }
 
+/// The pairings checked by [`AnalyzeParameters`], extend this table to flag new ones.
+pub(crate) const COMPATIBILITY_RULES: &[CompatibilityRule] = &[
+    CompatibilityRule {
+        language: "Bash",
+        architecture: "Microservices",
+        reason: "shell scripts are a poor fit for independently deployed services",
+    },
+    CompatibilityRule {
+        language: "Bash",
+        architecture: "Event Sourcing",
+        reason: "shell scripts have no practical way to keep an event store",
+    },
+    CompatibilityRule {
+        language: "COBOL",
+        architecture: "Serverless",
+        reason: "COBOL is barely supported by serverless platforms",
+    },
+    CompatibilityRule {
+        language: "C",
+        architecture: "Serverless",
+        reason: "C is barely supported by serverless platforms",
+    },
+];
+
 #[allow(dead_code)]
 /// AnalyzeParameters is a struct that holds the parameters required for analyzing a business definition.
 ///
@@ -153,10 +297,12 @@ pub struct AnalyzeParameters {
     pub(crate) additional_prompt: Option<String>,
     pub(crate) use_c4: bool,
     pub(crate) only_json: bool,
+    pub(crate) strict: bool,
+    pub(crate) include_registry: bool,
+    pub(crate) mode: AnalyzeMode,
 }
 
 impl AnalyzeParameters {
-    #[allow(dead_code)]
     pub(crate) fn new(
         definition: Definition,
         version: FileVersion,
@@ -171,54 +317,469 @@ impl AnalyzeParameters {
             additional_prompt: None,
             use_c4: false,
             only_json: false,
+            strict: false,
+            include_registry: false,
+            mode: AnalyzeMode::default(),
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn with_additional_prompt(mut self, additional_prompt: String) -> Self {
         self.additional_prompt = Some(additional_prompt);
         self
     }
 
-    #[allow(dead_code)]
     pub(crate) fn with_use_c4(mut self, use_c4: bool) -> Self {
         self.use_c4 = use_c4;
         self
     }
 
-    #[allow(dead_code)]
     pub(crate) fn with_only_json(mut self, only_json: bool) -> Self {
         self.only_json = only_json;
         self
     }
+
+    /// `with_strict` turns an incompatible language and architecture pairing into a
+    /// validation error, it's only a warning otherwise.
+    pub(crate) fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// `incompatibility` returns the first rule flagging the language and architecture pairing.
+    pub(crate) fn incompatibility<'a>(
+        &self,
+        rules: &'a [CompatibilityRule],
+    ) -> Option<&'a CompatibilityRule> {
+        let language = self.language.as_ref()?.as_str();
+        let architecture = self.architecture.as_ref()?.as_str();
+
+        rules.iter().find(|rule| {
+            rule.language.eq_ignore_ascii_case(language)
+                && rule.architecture.eq_ignore_ascii_case(architecture)
+        })
+    }
+
+    /// `with_include_registry` adds a summary of the other registered definitions to the prompt.
+    pub(crate) fn with_include_registry(mut self, include_registry: bool) -> Self {
+        self.include_registry = include_registry;
+        self
+    }
+
+    /// `with_mode` selects the prompt template, see [`AnalyzeMode`].
+    pub(crate) fn with_mode(mut self, mode: AnalyzeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// `to_prompt` renders the prompt sent to the model for the given definition content.
+    pub(crate) fn to_prompt(&self, content: &str) -> String {
+        self.to_prompt_with_registry(content, None)
+    }
+
+    /// `to_prompt_with_registry` renders the prompt like [`AnalyzeParameters::to_prompt`],
+    /// with the given registry summary as additional context.
+    pub(crate) fn to_prompt_with_registry(&self, content: &str, registry: Option<&str>) -> String {
+        let task = match self.mode {
+            AnalyzeMode::Generate => format!(
+                "Analyze the business definition \"{}\" version {} below and design its technical architecture.",
+                self.definition.stem(),
+                self.version
+            ),
+            AnalyzeMode::Review => format!(
+                "Review the business definition \"{}\" version {} below and point out its gaps, such as missing invariants or ambiguous terms, without designing an architecture.",
+                self.definition.stem(),
+                self.version
+            ),
+        };
+        let mut lines = vec![
+            "You are a software architect applying Domain-Driven Design.".to_string(),
+            task,
+        ];
+
+        if let Some(language) = &self.language {
+            lines.push(format!("Programming language: {}", language.as_str()));
+        }
+
+        if let Some(architecture) = &self.architecture {
+            lines.push(format!("Architecture style: {}", architecture.as_str()));
+        }
+
+        if self.use_c4 && self.mode == AnalyzeMode::Generate {
+            lines.push("Describe the architecture using the C4 model.".to_string());
+        }
+
+        if self.only_json {
+            lines.push("Respond with a single JSON document and nothing else.".to_string());
+        }
+
+        if let Some(prompt) = self.additional_prompt.as_deref().filter(|p| !p.is_empty()) {
+            lines.push(format!("Additional context: {}", prompt));
+        }
+
+        if let Some(registry) = registry.filter(|r| !r.is_empty()) {
+            lines.push(format!(
+                "Other business definitions in the project, with their latest version:\n{}",
+                registry
+            ));
+        }
+
+        format!(
+            "{}\n\n--- business definition ---\n{}",
+            lines.join("\n"),
+            content
+        )
+    }
 }
 
 impl Validator for AnalyzeParameters {
     fn validate(&self) -> Result<(), CoreError> {
-        self.definition.validate()?;
-        self.version.validate()?;
+        let additional_prompt = self
+            .additional_prompt
+            .as_deref()
+            .map(AdditionalPrompt::from);
+
+        let mut items: Vec<&dyn Validator> = vec![&self.definition, &self.version];
+        if let Some(language) = &self.language {
+            items.push(language);
+        }
 
-        self.language
-            .as_ref()
-            .map(|val| val.validate())
-            .unwrap_or(Ok(()))?;
+        if let Some(architecture) = &self.architecture {
+            items.push(architecture);
+        }
 
-        self.architecture
-            .as_ref()
-            .map(|val| val.validate())
-            .unwrap_or(Ok(()))?;
+        if let Some(prompt) = &additional_prompt {
+            items.push(prompt);
+        }
 
-        if let Some(prompt) = &self.additional_prompt {
-            AdditionalPrompt::from(prompt.as_str()).validate()?;
+        validate_all(&items)?;
+
+        if let Some(rule) = self.incompatibility(COMPATIBILITY_RULES) {
+            let msg = format!(
+                "{} with {} is an unusual pairing: {}",
+                rule.language, rule.architecture, rule.reason
+            );
+            if self.strict {
+                return Err(CoreError::validation(msg));
+            }
+            warn!("{}", msg);
         }
 
         Ok(())
     }
 }
 
+/// `DefinitionFile` is a business definition version found on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DefinitionFile {
+    pub(crate) definition: Definition,
+    pub(crate) version: FileVersion,
+    pub(crate) layout: FileLayout,
+}
+
+/// `ExportReport` summarizes a registry export, dangling entries are listed as `{name} {version}`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ExportReport {
+    pub(crate) exported: usize,
+    pub(crate) dangling: Vec<String>,
+}
+
+/// `DefineReport` tells which version a define resolved to and where its file is, along
+/// with whether that version was newly registered.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DefineReport {
+    pub(crate) version: FileVersion,
+    pub(crate) path: PathBuf,
+    pub(crate) added: bool,
+}
+
+/// `ChunkedAnalysis` is the model output of a definition analyzed in several parts, in
+/// document order, along with the number of parts it took.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ChunkedAnalysis {
+    pub(crate) output: String,
+    pub(crate) chunks: usize,
+}
+
+/// `LintReport` lists the required sections missing from a single business definition version.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LintReport {
+    pub(crate) version: FileVersion,
+    pub(crate) missing: Vec<String>,
+}
+
+impl LintReport {
+    pub(crate) fn is_ok(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
 pub(crate) trait Processor {
     /// define is a method that defines a business definition with the given parameters.
     ///
     /// This method should be used to create a business definition in the system.
-    fn define(&self, definition: Definition, version: FileVersion) -> Result<(), BusinessError>;
+    /// The location decides where the file is placed, by default inside the businesses
+    /// directory. The file starts with the given content, empty for a blank definition.
+    /// It returns the path of the written file.
+    fn define(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+        content: &str,
+    ) -> Result<PathBuf, BusinessError>;
+
+    /// exists is a method that tells whether the file of a business definition version
+    /// is already on disk, registered or not.
+    fn exists(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: &FileLocation,
+    ) -> bool;
+
+    /// path is a method that tells where the file of a business definition version is,
+    /// whether it's written yet or not.
+    fn path(
+        &self,
+        definition: &Definition,
+        version: &FileVersion,
+        location: &FileLocation,
+    ) -> PathBuf;
+
+    /// export is a method that copies a business definition version into the target directory,
+    /// at the relative path its layout gives it in the businesses directory.
+    fn export(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+        target_dir: PathBuf,
+    ) -> Result<(), BusinessError>;
+
+    /// scan is a method that lists every business definition version found on disk,
+    /// whether it's registered or not.
+    fn scan(&self) -> Result<Vec<DefinitionFile>, BusinessError>;
+
+    /// read is a method that loads the whole content of a business definition version.
+    fn read(
+        &self,
+        definition: Definition,
+        version: FileVersion,
+        location: FileLocation,
+    ) -> Result<String, BusinessError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod test_definition {
+        use super::*;
+
+        #[test]
+        fn test_definition_filename_round_trip() {
+            for name in ["order", "order-service", "billing_v2"] {
+                let definition = Definition::from(name);
+                let file_name = FileName::from(definition.clone());
+                assert_eq!(file_name.as_str(), name);
+                assert_eq!(Definition::from(file_name), definition);
+            }
+        }
+
+        #[test]
+        fn test_definition_filename_round_trip_by_ref() {
+            let definition = Definition::from("order");
+            let file_name = FileName::from(&definition);
+            assert_eq!(Definition::from(&file_name), definition);
+        }
+
+        #[test]
+        fn test_definition_length_boundary() {
+            let definition = Definition::from("a".repeat(MAX_DEFINITION_LENGTH));
+            assert!(definition.validate().is_ok());
+
+            let definition = Definition::from("a".repeat(MAX_DEFINITION_LENGTH + 1));
+            match definition.validate() {
+                Err(CoreError::ValidationError { message: msg, .. }) => {
+                    assert!(msg.contains("256 bytes long, the maximum is 255 bytes"))
+                }
+                _ => panic!("Expected ValidationError"),
+            }
+        }
+
+        #[test]
+        fn test_definition_custom_max_len() {
+            let definition = Definition::from("order");
+            assert!(definition.validate_with_max_len(5).is_ok());
+            assert!(definition.validate_with_max_len(4).is_err());
+        }
+
+        #[test]
+        fn test_definition_rejects_control_characters() {
+            for name in ["order\n", "or\tder", "order\u{7f}"] {
+                match Definition::from(name).validate() {
+                    Err(CoreError::ValidationError { message: msg, .. }) => {
+                        assert!(msg.contains("control characters"))
+                    }
+                    _ => panic!("Expected ValidationError for {:?}", name),
+                }
+            }
+        }
+
+        #[test]
+        fn test_definition_to_filename_strips_extension() {
+            let file_name = FileName::from(Definition::from("order.md"));
+            assert_eq!(file_name, FileName::from("order"));
+            assert_eq!(Definition::from(file_name), Definition::from("order"));
+        }
+    }
+
+    mod test_analyze_parameters {
+        use super::*;
+
+        #[test]
+        fn test_to_prompt() {
+            let params = AnalyzeParameters::new(
+                Definition::from("order.md"),
+                FileVersion::from("1.0.0"),
+                Some(Language::from("Rust")),
+                None,
+            )
+            .with_use_c4(true)
+            .with_additional_prompt("Keep it small".to_string());
+
+            let prompt = params.to_prompt("## Ubiquitous Language\n");
+            assert!(prompt.contains("\"order\" version 1.0.0"));
+            assert!(prompt.contains("Programming language: Rust"));
+            assert!(!prompt.contains("Architecture style"));
+            assert!(prompt.contains("C4 model"));
+            assert!(!prompt.contains("JSON"));
+            assert!(prompt.contains("Additional context: Keep it small"));
+            assert!(prompt.ends_with("--- business definition ---\n## Ubiquitous Language\n"));
+        }
+
+        #[test]
+        fn test_compatible_pairing() {
+            let params = AnalyzeParameters::new(
+                Definition::from("order"),
+                FileVersion::from("1.0.0"),
+                Some(Language::from("Rust")),
+                Some(Architecture::from("Modular Monolith")),
+            )
+            .with_strict(true);
+
+            assert!(params.incompatibility(COMPATIBILITY_RULES).is_none());
+            assert!(params.validate().is_ok());
+        }
+
+        #[test]
+        fn test_flagged_pairing() {
+            let params = AnalyzeParameters::new(
+                Definition::from("order"),
+                FileVersion::from("1.0.0"),
+                Some(Language::from("bash")),
+                Some(Architecture::from("microservices")),
+            );
+
+            let rule = params.incompatibility(COMPATIBILITY_RULES).unwrap();
+            assert_eq!(rule.language, "Bash");
+            assert!(params.validate().is_ok(), "only a warning by default");
+
+            match params.with_strict(true).validate() {
+                Err(CoreError::ValidationError { message: msg, .. }) => {
+                    assert!(msg.contains("Bash with Microservices is an unusual pairing"))
+                }
+                _ => panic!("Expected ValidationError"),
+            }
+        }
+
+        #[test]
+        fn test_custom_rules() {
+            let rules = [CompatibilityRule {
+                language: "Rust",
+                architecture: "Modular Monolith",
+                reason: "testing",
+            }];
+            let params = AnalyzeParameters::new(
+                Definition::from("order"),
+                FileVersion::from("1.0.0"),
+                Some(Language::from("Rust")),
+                Some(Architecture::from("Modular Monolith")),
+            );
+
+            assert_eq!(params.incompatibility(&rules), Some(&rules[0]));
+        }
+
+        #[test]
+        fn test_review_mode() {
+            let params = AnalyzeParameters::new(
+                Definition::from("order.md"),
+                FileVersion::from("1.0.0"),
+                None,
+                None,
+            )
+            .with_use_c4(true)
+            .with_mode(AnalyzeMode::Review);
+
+            let prompt = params.to_prompt("## Ubiquitous Language\n");
+            assert!(prompt.contains("Review the business definition \"order\" version 1.0.0"));
+            assert!(prompt.contains("missing invariants"));
+            assert!(!prompt.contains("design its technical architecture"));
+            assert!(!prompt.contains("C4 model"));
+        }
+    }
+
+    mod test_business_error {
+        use super::*;
+        use std::error::Error as StdError;
+        use std::io::ErrorKind;
+
+        #[test]
+        fn test_registry_error_source_chain() {
+            let io_err = IoError::new(ErrorKind::NotFound, "registry.json");
+            let err = BusinessError::from(RegistryError::from(io_err));
+
+            let registry_err = err.source().expect("registry error should be the source");
+            assert!(matches!(
+                registry_err.downcast_ref::<RegistryError>(),
+                Some(RegistryError::FsError(_))
+            ));
+
+            let root = registry_err
+                .source()
+                .expect("io error should be the root source");
+            let root = root.downcast_ref::<IoError>().unwrap();
+            assert_eq!(root.kind(), ErrorKind::NotFound);
+            assert!(root.source().is_none());
+        }
+
+        #[test]
+        fn test_project_error_source_chain() {
+            let core_err = CoreError::validation("empty name");
+            let err = BusinessError::from(ProjectError::from(core_err));
+
+            let project_err = err.source().expect("project error should be the source");
+            assert!(project_err.downcast_ref::<ProjectError>().is_some());
+
+            let root = project_err
+                .source()
+                .expect("core error should be the root source");
+            assert_eq!(
+                root.to_string(),
+                "[core error] validation error: empty name"
+            );
+            assert!(root.source().is_none());
+        }
+
+        #[test]
+        fn test_message_only_variants_have_no_source() {
+            let err = BusinessError::DefinitionNotFound("order".to_string());
+            assert!(err.source().is_none());
+
+            let err = BusinessError::from(DocumentError::NotFound("order/0.1.0.md".to_string()));
+            let document_err = err.source().unwrap();
+            assert!(document_err.source().is_none());
+        }
+    }
 }
@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::core::document::types::{DocumentError, FilePath, FileReader, FileWriter, FsProcessor};
+
+/// `MemoryFs` keeps documents and directories in memory by path, so a flow writing files
+/// can be exercised without touching the disk.
+///
+/// Clones share the same content, a clone kept by the test sees what was written.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryFs {
+    files: Rc<RefCell<BTreeMap<PathBuf, Vec<u8>>>>,
+    dirs: Rc<RefCell<BTreeSet<PathBuf>>>,
+}
+
+impl MemoryFs {
+    pub(crate) fn new() -> Self {
+        MemoryFs::default()
+    }
+
+    /// `with_dir` seeds a directory, e.g. the root documents are written into.
+    pub(crate) fn with_dir(self, path: impl Into<PathBuf>) -> Self {
+        self.dirs.borrow_mut().insert(path.into());
+        self
+    }
+
+    /// `with_file` seeds a document at the given path, replacing any previous one.
+    pub(crate) fn with_file(self, path: impl Into<PathBuf>, content: impl Into<Vec<u8>>) -> Self {
+        self.files.borrow_mut().insert(path.into(), content.into());
+        self
+    }
+
+    /// `file` returns the content of the document at the given path as text.
+    pub(crate) fn file(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.files
+            .borrow()
+            .get(path.as_ref())
+            .map(|content| String::from_utf8_lossy(content).into_owned())
+    }
+
+    /// `is_dir` tells whether a directory exists at the given path.
+    pub(crate) fn is_dir(&self, path: impl AsRef<Path>) -> bool {
+        self.dirs.borrow().contains(path.as_ref())
+    }
+
+    fn parent_exists(&self, path: &Path) -> bool {
+        path.parent().is_some_and(|parent| self.is_dir(parent))
+    }
+}
+
+impl FsProcessor for MemoryFs {
+    fn read(&self, path: FilePath) -> Result<FileReader, DocumentError> {
+        let path = path.to_path_buf();
+        let content = self
+            .files
+            .borrow()
+            .get(&path)
+            .cloned()
+            .ok_or_else(|| DocumentError::NotFound(path.display().to_string()))?;
+
+        Ok(Box::new(Cursor::new(content)))
+    }
+
+    fn write(&self, path: FilePath, _content: FileWriter) -> Result<(), DocumentError> {
+        Err(DocumentError::WriteError(format!(
+            "{} is bound to a file on disk, use write_all instead",
+            path.to_path_buf().display()
+        )))
+    }
+
+    fn write_all(&self, path: FilePath, content: &[u8]) -> Result<(), DocumentError> {
+        let path = path.to_path_buf();
+        if !self.parent_exists(&path) || self.is_dir(&path) {
+            return Err(DocumentError::WriteError(format!(
+                "unable to write {}",
+                path.display()
+            )));
+        }
+
+        self.files.borrow_mut().insert(path, content.to_vec());
+        Ok(())
+    }
+
+    fn exists(&self, path: FilePath) -> bool {
+        let path = path.to_path_buf();
+        self.is_dir(&path) || self.files.borrow().contains_key(&path)
+    }
+
+    fn create_dir(&self, path: FilePath) -> Result<(), DocumentError> {
+        let path_buf = path.to_path_buf();
+        if !self.parent_exists(&path_buf) || self.exists(path) {
+            return Err(DocumentError::WriteError(format!(
+                "unable to create directory {}",
+                path_buf.display()
+            )));
+        }
+
+        self.dirs.borrow_mut().insert(path_buf);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_fs() {
+        let fs = MemoryFs::new().with_dir("/memory");
+        let order = PathBuf::from("/memory/order/1.0.0.md");
+
+        assert!(fs
+            .write_all(FilePath::from(order.clone()), b"# Order")
+            .is_err());
+        fs.create_dir(FilePath::from("/memory/order")).unwrap();
+        assert!(fs.create_dir(FilePath::from("/memory/order")).is_err());
+
+        fs.clone()
+            .write_all(FilePath::from(order.clone()), b"# Order")
+            .unwrap();
+        assert!(fs.exists(FilePath::from(order.clone())));
+        assert_eq!(fs.file(&order).unwrap(), "# Order");
+        assert_eq!(
+            fs.read_to_string(FilePath::from(order.clone())).unwrap(),
+            "# Order"
+        );
+
+        assert!(matches!(
+            fs.read(FilePath::from("/memory/billing/1.0.0.md")),
+            Err(DocumentError::NotFound(_))
+        ));
+    }
+}
@@ -1 +1,3 @@
+#[cfg(test)]
+pub(crate) mod memory;
 pub(crate) mod types;
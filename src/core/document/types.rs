@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufRead, BufWriter, Read};
 use std::path::PathBuf;
 
 use thiserror::Error;
@@ -39,7 +39,13 @@ impl From<&str> for FilePath {
     }
 }
 
-pub(crate) type FileReader = BufReader<File>;
+impl From<PathBuf> for FilePath {
+    fn from(path: PathBuf) -> Self {
+        FilePath(path.to_string_lossy().into_owned())
+    }
+}
+
+pub(crate) type FileReader = Box<dyn BufRead>;
 pub(crate) type FileWriter = BufWriter<File>;
 
 #[allow(dead_code)]
@@ -52,6 +58,28 @@ pub(crate) trait FsProcessor {
     /// but a buffered reader that can be used to read the document in chunks.
     fn read(&self, path: FilePath) -> Result<FileReader, DocumentError>;
 
+    /// Reads a whole document into a `String`.
+    ///
+    /// It's built on top of [`FsProcessor::read`], which should still be preferred for large
+    /// documents. Content which is not valid UTF-8 is reported as a [`DocumentError::ReadError`].
+    fn read_to_string(&self, path: FilePath) -> Result<String, DocumentError> {
+        let mut content = String::new();
+        self.read(path)?
+            .read_to_string(&mut content)
+            .map_err(|err| DocumentError::ReadError(err.to_string()))?;
+
+        Ok(content)
+    }
+
     /// Write a document to the specified path.
     fn write(&self, path: FilePath, content: FileWriter) -> Result<(), DocumentError>;
+
+    /// Writes a whole document to the specified path, replacing any previous content.
+    fn write_all(&self, path: FilePath, content: &[u8]) -> Result<(), DocumentError>;
+
+    /// Tells whether a document or a directory exists at the specified path.
+    fn exists(&self, path: FilePath) -> bool;
+
+    /// Creates a directory at the specified path, its parent has to exist already.
+    fn create_dir(&self, path: FilePath) -> Result<(), DocumentError>;
 }
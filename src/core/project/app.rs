@@ -1,8 +1,9 @@
+use chrono::{DateTime, Utc};
 use tracing::{info, instrument};
 
 use crate::core::types::validate;
 
-use super::types::{Builder, Desc, Name, Project, ProjectError};
+use super::types::{Builder, Desc, InitiatedPath, Name, Project, ProjectError};
 
 #[derive(Debug, Clone)]
 pub(crate) struct App<T>
@@ -10,6 +11,7 @@ where
     T: Builder,
 {
     builder: T,
+    clock: fn() -> DateTime<Utc>,
 }
 
 impl<T> App<T>
@@ -17,34 +19,66 @@ where
     T: Builder,
 {
     pub fn new(builder: T) -> Self {
-        App { builder }
+        App {
+            builder,
+            clock: Utc::now,
+        }
+    }
+
+    /// `with_clock` replaces the current time recorded as the project creation time, so it
+    /// can be fixed in tests.
+    #[cfg(test)]
+    pub(crate) fn with_clock(mut self, clock: fn() -> DateTime<Utc>) -> Self {
+        self.clock = clock;
+        self
     }
 
     #[instrument(skip_all, err)]
-    pub fn init(&self, name: Name, desc: Option<Desc>) -> Result<(), ProjectError> {
+    /// `init` validates and builds a new project.
+    ///
+    /// It returns the created [`Project`], so callers get its generated fields such as
+    /// `created_at`, along with every path touched by the builder. An already initialized
+    /// project is only overwritten when `force` is set.
+    pub fn init(
+        &self,
+        name: Name,
+        desc: Option<Desc>,
+        author: Option<String>,
+        force: bool,
+    ) -> Result<(Project, Vec<InitiatedPath>), ProjectError> {
         info!("Initializing project with name: {}", name.as_str());
-        let project = Project::new(name, desc);
+        let project = Project::new(name, desc)
+            .with_author(author)
+            .with_created_at((self.clock)());
 
         info!("Validating project");
-        let _ = validate(&project).map_err(|err| ProjectError::ValidationError(err))?;
+        validate(&project).map_err(ProjectError::ValidationError)?;
 
         info!("Project validation successful, start build project");
-        self.builder
-            .initiate(project)
-            .map_err(|err| ProjectError::InitiateError(err.to_string()))
+        let paths = self
+            .builder
+            .initiate(project.clone(), force)
+            .map_err(|err| match err {
+                ProjectError::AlreadyInitialized(_) => err,
+                err => ProjectError::InitiateError(err.to_string()),
+            })?;
+
+        Ok((project, paths))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use super::*;
-    use mockall::mock;
+    use mockall::{mock, predicate::always, predicate::eq};
 
     mock!(
         FakeAppBuilder{}
 
         impl Builder for FakeAppBuilder {
-            fn initiate(&self, project: Project) -> Result<(), ProjectError>;
+            fn initiate(&self, project: Project, force: bool) -> Result<Vec<InitiatedPath>, ProjectError>;
         }
     );
 
@@ -54,13 +88,13 @@ mod tests {
         #[test]
         fn test_fail_on_validation() {
             let mut builder = MockFakeAppBuilder::new();
-            builder.expect_initiate().returning(|_| Ok(()));
+            builder.expect_initiate().returning(|_, _| Ok(vec![]));
 
             let app = App::new(builder);
             let name = Name::from(""); // Empty name to trigger validation error
             let desc = Some(Desc::from("This is a test project"));
 
-            let result = app.init(name, desc);
+            let result = app.init(name, desc, None, false);
             assert!(result.is_err());
 
             let err = result.unwrap_err();
@@ -76,7 +110,7 @@ mod tests {
         #[test]
         fn test_initiate_error() {
             let mut builder = MockFakeAppBuilder::new();
-            builder.expect_initiate().returning(|_| {
+            builder.expect_initiate().returning(|_, _| {
                 Err(ProjectError::InitiateError(
                     "Failed to initiate".to_string(),
                 ))
@@ -86,7 +120,7 @@ mod tests {
             let name = Name::from("Test Project");
             let desc = Some(Desc::from("This is a test project"));
 
-            let result = app.init(name, desc);
+            let result = app.init(name, desc, None, false);
             assert!(result.is_err());
 
             let err = result.unwrap_err();
@@ -98,16 +132,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_already_initialized() {
+        let mut builder = MockFakeAppBuilder::new();
+        builder
+            .expect_initiate()
+            .with(always(), eq(false))
+            .returning(|_, _| {
+                Err(ProjectError::AlreadyInitialized(
+                    ".ddai/project.json".to_string(),
+                ))
+            });
+
+        let app = App::new(builder);
+        let result = app.init(Name::from("Test Project"), None, None, false);
+        assert!(matches!(result, Err(ProjectError::AlreadyInitialized(_))));
+        assert!(result.unwrap_err().to_string().contains("--force"));
+    }
+
+    #[test]
+    fn test_forced_initiation() {
+        let mut builder = MockFakeAppBuilder::new();
+        builder
+            .expect_initiate()
+            .with(always(), eq(true))
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![InitiatedPath::new(
+                    PathBuf::from(".ddai/project.json"),
+                    true,
+                )])
+            });
+
+        let app = App::new(builder);
+        let result = app.init(Name::from("Test Project"), None, None, true);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_successful_initiation() {
         let mut builder = MockFakeAppBuilder::new();
-        builder.expect_initiate().returning(|_| Ok(()));
+        builder.expect_initiate().returning(|_, _| {
+            Ok(vec![
+                InitiatedPath::new(PathBuf::from(".ddai"), true),
+                InitiatedPath::new(PathBuf::from("businesses"), false),
+            ])
+        });
 
         let app = App::new(builder);
         let name = Name::from("Test Project");
         let desc = Some(Desc::from("This is a test project"));
 
-        let result = app.init(name, desc);
+        let result = app.init(name, desc, Some("jane".to_string()), false);
         assert!(result.is_ok());
+
+        let (project, paths) = result.unwrap();
+        assert_eq!(project.name.as_str(), "Test Project");
+        assert_eq!(project.author.as_deref(), Some("jane"));
+        assert_eq!(
+            project.description.unwrap().as_str(),
+            "This is a test project"
+        );
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].created);
+        assert!(!paths[1].created);
     }
 }
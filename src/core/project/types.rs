@@ -1,7 +1,12 @@
+use std::fmt;
+use std::path::PathBuf;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::core::document::types::DocumentError;
+use crate::core::registry::types::RegistryFormat;
 use crate::core::types::{CoreError, ToJSON, Validator};
 
 pub const PROJECT_DIR_NAME: &str = ".ddai";
@@ -10,6 +15,7 @@ pub const PROJECT_CREDENTIAL_NAME: &str = "credentials.json";
 pub const PROJECT_BUSINESS_DIR_NAME: &str = "businesses";
 pub const PROJECT_ARCHITECTURE_DIR_NAME: &str = "architectures";
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub(crate) enum ProjectError {
     #[error("[project error] project unable to initiate: {0}")]
@@ -18,8 +24,17 @@ pub(crate) enum ProjectError {
     #[error("[project error] filesystem error: {0}")]
     FsError(#[from] std::io::Error),
 
+    #[error("[project error] document error: {0}")]
+    DocumentError(#[from] DocumentError),
+
     #[error("[project error] validation error: {0}")]
     ValidationError(#[from] CoreError),
+
+    #[error("[project error] project already initialized at {0}, use --force to overwrite it")]
+    AlreadyInitialized(String),
+
+    #[error("[project error] no project found in {0}, run `ddai project init` first")]
+    NotInitialized(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,10 +44,11 @@ impl Name {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+}
 
-    #[allow(dead_code)]
-    pub fn to_string(&self) -> String {
-        self.0.to_owned()
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
@@ -54,17 +70,28 @@ impl From<&str> for Name {
     }
 }
 
+impl Validator for Name {
+    fn validate(&self) -> Result<(), CoreError> {
+        if self.0.trim().is_empty() {
+            return Err(CoreError::field("name", "Project name cannot be empty"));
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Desc(String);
 
 impl Desc {
     pub fn as_str(&self) -> &str {
-        &self.0.as_str()
+        self.0.as_str()
     }
+}
 
-    #[allow(dead_code)]
-    pub fn to_string(&self) -> String {
-        self.0.to_owned()
+impl fmt::Display for Desc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
@@ -86,6 +113,51 @@ impl From<&str> for Desc {
     }
 }
 
+impl Validator for Desc {
+    fn validate(&self) -> Result<(), CoreError> {
+        if self.0.trim().is_empty() {
+            return Err(CoreError::field(
+                "desc",
+                "Project description cannot be empty",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// `LintConfig` holds the project level settings used when linting business definitions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub(crate) struct LintConfig {
+    #[serde(default)]
+    pub(crate) required_sections: Vec<String>,
+}
+
+/// `RegistryConfig` holds the project level policies applied to the business registry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub(crate) struct RegistryConfig {
+    /// The maximum number of business definitions, unlimited when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_files: Option<usize>,
+
+    /// The maximum number of versions of a single business definition, unlimited when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_versions: Option<usize>,
+
+    /// The registry file format, JSON when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<RegistryFormat>,
+
+    /// Allow business names only differing by case, only safe on case-sensitive filesystems.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) case_sensitive: Option<bool>,
+
+    /// A shell command run after `business define` or `business doctor --fix-orphans`
+    /// changed the registry, see `business define --on-change`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) on_change: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Project {
     pub(crate) name: Name,
@@ -94,6 +166,20 @@ pub(crate) struct Project {
     pub(crate) description: Option<Desc>,
 
     pub(crate) created_at: DateTime<Utc>,
+
+    /// When the project file was last rewritten, absent until it is modified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) updated_at: Option<DateTime<Utc>>,
+
+    /// Who created the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) lint: Option<LintConfig>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) registry: Option<RegistryConfig>,
 }
 
 impl Default for Project {
@@ -102,6 +188,10 @@ impl Default for Project {
             name: Name::default(),
             description: None,
             created_at: Utc::now(),
+            updated_at: None,
+            author: None,
+            lint: None,
+            registry: None,
         }
     }
 }
@@ -112,23 +202,46 @@ impl Project {
             name,
             description,
             created_at: Utc::now(),
+            updated_at: None,
+            author: None,
+            lint: None,
+            registry: None,
         }
     }
+
+    pub fn with_author(mut self, author: Option<String>) -> Self {
+        self.author = author;
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Marks the project as modified at the given time, to be called before each write of
+    /// the project file.
+    pub(crate) fn touch(&mut self, at: DateTime<Utc>) {
+        self.updated_at = Some(at);
+    }
+
+    /// Returns when the project was last modified, its creation time if it never was.
+    pub(crate) fn last_updated(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or(self.created_at)
+    }
 }
 
 impl Validator for Project {
     fn validate(&self) -> Result<(), CoreError> {
-        if self.name.as_str().is_empty() {
-            return Err(CoreError::ValidationError(
-                "Project name cannot be empty".to_string(),
-            ));
-        }
+        self.name.validate()?;
 
         if let Some(desc) = &self.description {
-            if desc.as_str().is_empty() {
-                return Err(CoreError::ValidationError(
-                    "Project description cannot be empty".to_string(),
-                ));
+            desc.validate()?;
+        }
+
+        if let Some(author) = &self.author {
+            if author.trim().is_empty() {
+                return Err(CoreError::field("author", "Project author cannot be empty"));
             }
         }
 
@@ -138,8 +251,27 @@ impl Validator for Project {
 
 impl ToJSON for Project {}
 
+/// `InitiatedPath` describes a single filesystem entry touched while initiating a project.
+///
+/// The `created` flag tells whether the entry was newly created or was already there.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct InitiatedPath {
+    pub(crate) path: PathBuf,
+    pub(crate) created: bool,
+}
+
+impl InitiatedPath {
+    pub fn new(path: PathBuf, created: bool) -> Self {
+        InitiatedPath { path, created }
+    }
+}
+
 pub(crate) trait Builder {
-    fn initiate(&self, project: Project) -> Result<(), ProjectError>;
+    /// Builds the project layout and returns every path it touched, in creation order.
+    ///
+    /// An existing project file is an [`ProjectError::AlreadyInitialized`] error unless
+    /// `force` is set, in which case it gets overwritten.
+    fn initiate(&self, project: Project, force: bool) -> Result<Vec<InitiatedPath>, ProjectError>;
 }
 
 #[cfg(test)]
@@ -166,6 +298,38 @@ mod tests {
         assert!(project.description.is_none());
     }
 
+    #[test]
+    fn test_updated_at() {
+        let json = r#"{"name":"Sample Project","created_at":"2025-01-01T00:00:00Z"}"#;
+        let mut project: Project = serde_json::from_str(json).unwrap();
+        assert!(project.updated_at.is_none());
+        assert_eq!(project.last_updated(), project.created_at);
+        assert!(!project.to_json().unwrap().contains("updated_at"));
+
+        project.touch(Utc::now());
+        assert!(project.last_updated() > project.created_at);
+        assert!(project.to_json().unwrap().contains("updated_at"));
+    }
+
+    #[test]
+    fn test_lint_config_is_optional() {
+        let json = r#"{"name":"Sample Project","created_at":"2025-01-01T00:00:00Z"}"#;
+        let project: Project = serde_json::from_str(json).unwrap();
+        assert!(project.lint.is_none());
+        assert!(project.author.is_none());
+
+        let json = r#"{
+            "name": "Sample Project",
+            "created_at": "2025-01-01T00:00:00Z",
+            "lint": { "required_sections": ["Aggregates"] }
+        }"#;
+        let project: Project = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            project.lint.unwrap().required_sections,
+            vec!["Aggregates".to_string()]
+        );
+    }
+
     #[test]
     fn test_to_json() {
         let project = Project::new(
@@ -191,7 +355,7 @@ mod tests {
                 assert!(result.is_err());
 
                 let err = result.unwrap_err();
-                assert!(matches!(err, CoreError::ValidationError(_)));
+                assert!(matches!(err, CoreError::ValidationError { .. }));
                 assert!(err.to_string().contains("Project name cannot be empty"));
             }
 
@@ -202,11 +366,38 @@ mod tests {
                 assert!(result.is_err());
 
                 let err = result.unwrap_err();
-                assert!(matches!(err, CoreError::ValidationError(_)));
+                assert!(matches!(err, CoreError::ValidationError { .. }));
+                assert!(err
+                    .to_string()
+                    .contains("Project description cannot be empty"));
+            }
+
+            #[test]
+            fn blank_name_and_description() {
+                let err = validate(&Name::from("  ")).unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    validate(&Name::default()).unwrap_err().to_string()
+                );
+
+                let project = Project::new(Name::from("Valid Name"), Some(Desc::from("\t\n")));
+                let err = validate(&project).unwrap_err();
                 assert!(err
                     .to_string()
                     .contains("Project description cannot be empty"));
             }
+
+            #[test]
+            fn empty_author() {
+                let project =
+                    Project::new(Name::from("Valid Name"), None).with_author(Some(" ".to_string()));
+                let result = validate(&project);
+                assert!(result.is_err());
+
+                let err = result.unwrap_err();
+                assert!(matches!(err, CoreError::ValidationError { .. }));
+                assert!(err.to_string().contains("Project author cannot be empty"));
+            }
         }
     }
 }
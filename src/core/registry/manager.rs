@@ -1,9 +1,11 @@
 use std::io::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
 
 use crate::core::registry::types::{
-    Directory, FileItem, FileName, FileVersion, Processor, Registry, RegistryError,
-    REGISTRY_FILE_NAME,
+    Directory, FileItem, FileLocation, FileName, FileVersion, Processor, Registry, RegistryError,
+    RegistryFormat, RegistryInfo,
 };
 
 use crate::core::types::{validate, PathBufWrapper};
@@ -16,6 +18,12 @@ where
 {
     processor: T,
     path_buf_wrapper: P,
+    max_files: Option<usize>,
+    max_versions: Option<usize>,
+    format: RegistryFormat,
+    case_sensitive: bool,
+    directory: Option<Directory>,
+    clock: fn() -> DateTime<Utc>,
 }
 
 impl<T, P> Manager<T, P>
@@ -34,6 +42,94 @@ where
         Manager {
             processor,
             path_buf_wrapper,
+            max_files: None,
+            max_versions: None,
+            format: RegistryFormat::default(),
+            case_sensitive: false,
+            directory: None,
+            clock: Utc::now,
+        }
+    }
+
+    /// `with_max_files` caps the number of files the registry may hold, `None` means unlimited.
+    ///
+    /// The cap only applies when a new file gets added, updating an existing file is always allowed.
+    pub(crate) fn with_max_files(mut self, max_files: Option<usize>) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// `with_max_versions` caps the number of versions a single file may hold, `None` means
+    /// unlimited.
+    ///
+    /// The cap only applies when a new version gets added, an already registered version is
+    /// always accepted again.
+    pub(crate) fn with_max_versions(mut self, max_versions: Option<usize>) -> Self {
+        self.max_versions = max_versions;
+        self
+    }
+
+    /// `with_format` sets the format of the registry file, which also decides its extension.
+    pub(crate) fn with_format(mut self, format: RegistryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// `with_case_sensitive` allows names only differing by case, which is only safe on
+    /// case-sensitive filesystems. They are rejected by default.
+    pub(crate) fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// `with_directory` sets the directory label of a newly built registry, usually the
+    /// name of the businesses directory. Without it, the label is the name of the output
+    /// directory, which differs when the registry is kept elsewhere.
+    pub(crate) fn with_directory(mut self, directory: Directory) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// `with_clock` replaces the current time recorded when a version gets added, so it can
+    /// be fixed in tests.
+    #[cfg(test)]
+    pub(crate) fn with_clock(mut self, clock: fn() -> DateTime<Utc>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// `check_collision` fails when a new file name only differs by case from a registered one.
+    ///
+    /// It always passes when the manager is case-sensitive or no registry exists yet.
+    pub(crate) fn check_collision(&self, file: &FileName) -> Result<(), RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        if self.case_sensitive || !self.processor.exists(&registry_file_path) {
+            return Ok(());
+        }
+
+        let registry = self.processor.parse(registry_file_path)?;
+        self._check_collision(&registry, file)
+    }
+
+    /// `check_capacity` fails when registering the version would exceed the files or the
+    /// versions cap.
+    ///
+    /// It always passes when no cap is set or no registry exists yet.
+    pub(crate) fn check_capacity(
+        &self,
+        file: &FileName,
+        version: &FileVersion,
+    ) -> Result<(), RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        let uncapped = self.max_files.is_none() && self.max_versions.is_none();
+        if uncapped || !self.processor.exists(&registry_file_path) {
+            return Ok(());
+        }
+
+        let registry = self.processor.parse(registry_file_path)?;
+        match registry.get_file(file) {
+            Some(file_item) => self._check_versions(file_item, version),
+            None => self._check_capacity(registry.files.len()),
         }
     }
 
@@ -41,22 +137,76 @@ where
     pub(crate) fn get_file(&self, file: FileName) -> Result<Option<FileItem>, RegistryError> {
         let registry_file_path = self._build_registry_file_path()?;
         let registry = self.processor.parse(registry_file_path)?;
-        let file_item = registry
-            .get_file(&file)
-            .and_then(|val| Some(val.to_owned()));
+        let file_item = registry.get_file(&file).map(|val| val.to_owned());
 
         Ok(file_item)
     }
 
+    /// `snapshot` returns an owned copy of the whole registry.
+    ///
+    /// When no registry file exists yet, an empty registry labelled after the output
+    /// directory is returned instead.
+    pub(crate) fn snapshot(&self) -> Result<Registry, RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        if self.processor.exists(&registry_file_path) {
+            return self.processor.parse(registry_file_path);
+        }
+
+        let directory = self._directory()?;
+        Ok(Registry::new(directory))
+    }
+
+    /// `inspect` describes the registry file as found on disk, `None` when there is none.
+    pub(crate) fn inspect(&self) -> Result<Option<RegistryInfo>, RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        if !self.processor.exists(&registry_file_path) {
+            return Ok(None);
+        }
+
+        self.processor.inspect(registry_file_path).map(Some)
+    }
+
+    /// `export` writes the given registry into another directory, in the manager's format.
+    ///
+    /// It returns the path of the written registry file.
+    pub(crate) fn export(
+        &self,
+        target_dir: &Path,
+        registry: Registry,
+    ) -> Result<PathBuf, RegistryError> {
+        let file_path = target_dir.join(self.format.file_name());
+        self.write(file_path.clone(), registry)?;
+        Ok(file_path)
+    }
+
+    /// `delete_registry_file` removes the registry file, e.g. to clean up after a test.
+    ///
+    /// A missing registry file is not an error, the processor refuses to remove anything
+    /// that isn't a regular file.
+    #[cfg(test)]
+    pub(crate) fn delete_registry_file(&self) -> Result<(), RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        if !self.processor.exists(&registry_file_path) {
+            return Ok(());
+        }
+
+        self.processor.remove(&registry_file_path)
+    }
+
     /// `build_registry` used to create a new registry file in the specified output directory.
-    /// It takes a file name as input, constructs a [`FileItem`] from it
+    /// It takes a file name and its first version as input, constructs a [`FileItem`] from them
     ///
     /// This method initializes a new [`Registry`] with the directory name derived from the output directory,
     /// adds the file item to the registry, and then calls the processor's `build` method
     ///
     /// This method should be used when you want to create a registry file for the first time
     #[allow(dead_code)]
-    pub(crate) fn build_registry(&self, file: FileName) -> Result<(), RegistryError> {
+    pub(crate) fn build_registry(
+        &self,
+        file: FileName,
+        version: FileVersion,
+        location: impl Into<FileLocation>,
+    ) -> Result<(), RegistryError> {
         if !self.path_buf_wrapper.exists() {
             return Err(RegistryError::FsError(Error::new(
                 std::io::ErrorKind::NotFound,
@@ -65,28 +215,23 @@ where
         }
 
         let registry_file_path = self._build_registry_file_path()?;
-        if registry_file_path.exists() {
+        if self.processor.exists(&registry_file_path) {
             return Err(RegistryError::FsError(Error::new(
                 std::io::ErrorKind::AlreadyExists,
                 "Registry file already exists",
             )));
         }
 
-        let dir_name = self
-            .path_buf_wrapper
-            .dir_name()
-            .ok_or(RegistryError::FsError(Error::new(
-                std::io::ErrorKind::NotFound,
-                "Output directory is missing or invalid",
-            )))?;
-
-        let file_item = FileItem::new(file);
-        let _ = validate(&file_item).map_err(|e| RegistryError::CoreError(e))?;
+        let directory = self._directory()?;
+        self._check_capacity(0)?;
 
-        let mut registry = Registry::new(Directory::from(dir_name));
-        registry.add_file(file_item);
+        let file_item = FileItem::from_version(file, version)
+            .with_location(location.into())
+            .with_updated_at((self.clock)());
+        validate(&file_item).map_err(RegistryError::CoreError)?;
 
-        self.processor.build(registry_file_path, registry)
+        let registry = Registry::from_files(directory, vec![file_item]);
+        self.write(registry_file_path, registry)
     }
 
     /// `update_registry` is used to update an existing registry file with a new file version
@@ -96,39 +241,165 @@ where
     ///
     /// This method should be used when you want to update the version of an existing file in the registry
     /// When updating the registry, it should not add a new file item if it already exists
+    ///
+    /// The location is only recorded for a new file item, an existing one keeps its own
+    ///
+    /// It returns `true` when the version was newly registered, `false` when it already was
     pub(crate) fn update_registry(
         &self,
         file: FileName,
         version: FileVersion,
-    ) -> Result<(), RegistryError> {
+        location: impl Into<FileLocation>,
+    ) -> Result<bool, RegistryError> {
+        validate(&version).map_err(RegistryError::CoreError)?;
+
         let registry_file_path = self._build_registry_file_path()?;
-        if !registry_file_path.exists() {
-            return self.build_registry(file);
+        if !self.processor.exists(&registry_file_path) {
+            return self.build_registry(file, version, location).map(|_| true);
         }
 
-        let _ = validate(&version).map_err(|e| RegistryError::CoreError(e))?;
-
         let mut registry = self.processor.parse(registry_file_path.clone())?;
-        let mut file_item = registry
-            .get_file(&FileName::from(file.clone()))
-            .and_then(|val| Some(val.to_owned()));
-
-        let file_item_mut = file_item.as_mut();
-        match file_item_mut {
-            Some(fi) => {
-                fi.update(version);
-                registry.add_file(fi.to_owned());
+        let added = match registry.get_file(&file) {
+            Some(file_item) => {
+                self._check_versions(file_item, &version)?;
+                let added = registry.upsert_version(&file, version);
+                if added {
+                    registry.touch(&file, (self.clock)());
+                }
+                added
             }
             None => {
-                registry.add_file(FileItem::new(file));
+                self._check_collision(&registry, &file)?;
+                self._check_capacity(registry.files.len())?;
+                registry.add_file(
+                    FileItem::from_version(file, version)
+                        .with_location(location.into())
+                        .with_updated_at((self.clock)()),
+                );
+                true
             }
+        };
+
+        self.write(registry_file_path, registry)?;
+        Ok(added)
+    }
+
+    /// `deduplicate` finds the duplicate files and versions of the registry, see
+    /// [`Registry::deduplicate`], and rewrites it without them when `repair` is set.
+    ///
+    /// It returns a description of every duplicate, empty when there is no registry yet.
+    pub(crate) fn deduplicate(&self, repair: bool) -> Result<Vec<String>, RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        if !self.processor.exists(&registry_file_path) {
+            return Ok(vec![]);
+        }
+
+        let mut registry = self.processor.parse(registry_file_path.clone())?;
+        let duplicates = registry.deduplicate();
+        if repair && !duplicates.is_empty() {
+            self.write(registry_file_path, registry)?;
         }
 
-        self.processor.build(registry_file_path, registry)
+        Ok(duplicates)
+    }
+
+    /// `set_domain` groups a registered file under the given domain.
+    ///
+    /// It returns `true` when the domain changed, the registry file is only rewritten then.
+    pub(crate) fn set_domain(
+        &self,
+        file: &FileName,
+        domain: String,
+    ) -> Result<bool, RegistryError> {
+        let registry_file_path = self._build_registry_file_path()?;
+        let mut registry = self.processor.parse(registry_file_path.clone())?;
+        if !registry.set_domain(file, domain) {
+            return Ok(false);
+        }
+
+        if let Some(file_item) = registry.get_file(file) {
+            validate(file_item).map_err(RegistryError::CoreError)?;
+        }
+
+        self.write(registry_file_path, registry)?;
+        Ok(true)
+    }
+
+    /// Persists the registry, every write goes through here so files left without any
+    /// version are always dropped first, see [`Registry::gc`].
+    fn write(&self, file_path: PathBuf, mut registry: Registry) -> Result<(), RegistryError> {
+        registry.gc();
+        self.processor.build(file_path, registry)
+    }
+
+    fn _check_collision(&self, registry: &Registry, file: &FileName) -> Result<(), RegistryError> {
+        if self.case_sensitive {
+            return Ok(());
+        }
+
+        match registry.case_collision(file) {
+            Some(existing) => Err(RegistryError::NameCollision(format!(
+                "{} only differs by case from the registered {}",
+                file.as_str(),
+                existing.name.as_str()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn _directory(&self) -> Result<Directory, RegistryError> {
+        let directory = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => self
+                .path_buf_wrapper
+                .dir_name()
+                .map(Directory::from)
+                .ok_or(RegistryError::FsError(Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Output directory is missing or invalid",
+                )))?,
+        };
+
+        validate(&directory).map_err(RegistryError::CoreError)?;
+        Ok(directory)
+    }
+
+    fn _check_capacity(&self, current_files: usize) -> Result<(), RegistryError> {
+        match self.max_files {
+            Some(max_files) if current_files >= max_files => {
+                Err(RegistryError::LimitExceeded(format!(
+                    "registry already holds {} of at most {} files",
+                    current_files, max_files
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn _check_versions(
+        &self,
+        file_item: &FileItem,
+        version: &FileVersion,
+    ) -> Result<(), RegistryError> {
+        let current_versions = file_item.versions.len();
+        match self.max_versions {
+            Some(max_versions)
+                if current_versions >= max_versions && !file_item.versions.contains(version) =>
+            {
+                Err(RegistryError::LimitExceeded(format!(
+                    "{} already holds {} of at most {} versions, remove old versions before adding {}",
+                    file_item.name.as_str(),
+                    current_versions,
+                    max_versions,
+                    version
+                )))
+            }
+            _ => Ok(()),
+        }
     }
 
     fn _build_registry_file_path(&self) -> Result<PathBuf, RegistryError> {
-        let file_path = self.path_buf_wrapper.to_path_buf().join(REGISTRY_FILE_NAME);
+        let file_path = self.path_buf_wrapper.join(self.format.file_name());
 
         Ok(file_path)
     }
@@ -139,6 +410,8 @@ mod tests {
     use std::path::PathBuf;
 
     use super::*;
+    use crate::core::registry::memory::{MemoryDir, MemoryProcessor};
+    use crate::core::registry::types::FileLayout;
     use mockall::{mock, predicate::*};
 
     mock!(
@@ -147,6 +420,8 @@ mod tests {
         impl Processor for FakeProcessor {
             fn build(&self, path: PathBuf, registry: Registry) -> Result<(), RegistryError>;
             fn parse(&self, path: PathBuf) -> Result<Registry, RegistryError>;
+            fn inspect(&self, path: PathBuf) -> Result<RegistryInfo, RegistryError>;
+            fn remove(&self, path: &Path) -> Result<(), RegistryError>;
         }
     );
 
@@ -160,6 +435,28 @@ mod tests {
         }
     );
 
+    /// A fixed time recorded as the time versions were added.
+    fn fixed_clock() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    const MEMORY_DIR: &str = "/memory/output";
+    const MEMORY_REGISTRY: &str = "/memory/output/registry.json";
+
+    /// A manager over an in-memory registry seeded with the given one, along with the
+    /// processor holding it so tests can look at what was written.
+    fn memory_manager(
+        registry: Registry,
+    ) -> (Manager<MemoryProcessor, MemoryDir>, MemoryProcessor) {
+        let processor = MemoryProcessor::new().with_registry(MEMORY_REGISTRY, registry);
+        (
+            Manager::new(processor.clone(), MemoryDir::new(MEMORY_DIR)),
+            processor,
+        )
+    }
+
     mod test_build_registry {
         use super::*;
         use std::fs::File;
@@ -169,7 +466,9 @@ mod tests {
         fn test_build_registry() {
             let expected_file_path = PathBuf::from("/tmp/output/registry.json");
             let mut expected_registry = Registry::new(Directory::from("output"));
-            expected_registry.add_file(FileItem::new(FileName::from("test_file")));
+            expected_registry.add_file(
+                FileItem::new(FileName::from("test_file")).with_updated_at(fixed_clock()),
+            );
 
             let mut processor = MockFakeProcessor::new();
             processor
@@ -188,8 +487,47 @@ mod tests {
 
             path_buf_wrapper.expect_exists().returning(|| true);
 
-            let manager = Manager::new(processor, path_buf_wrapper);
-            let result = manager.build_registry(FileName::from("test_file"));
+            let manager = Manager::new(processor, path_buf_wrapper).with_clock(fixed_clock);
+            let result = manager.build_registry(
+                FileName::from("test_file"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn test_build_registry_with_directory() {
+            let mut expected_registry = Registry::new(Directory::from("businesses"));
+            expected_registry.add_file(
+                FileItem::new(FileName::from("test_file")).with_updated_at(fixed_clock()),
+            );
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_build()
+                .with(
+                    eq(PathBuf::from("/tmp/registry/registry.json")),
+                    eq(expected_registry),
+                )
+                .returning(|_, _| Ok(()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(|| PathBuf::from("/tmp/registry"));
+            path_buf_wrapper.expect_dir_name().never();
+            path_buf_wrapper.expect_exists().returning(|| true);
+
+            let manager = Manager::new(processor, path_buf_wrapper)
+                .with_directory(Directory::from("businesses"))
+                .with_clock(fixed_clock);
+            let result = manager.build_registry(
+                FileName::from("test_file"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
 
             assert!(result.is_ok());
         }
@@ -221,7 +559,11 @@ mod tests {
             path_buf_wrapper.expect_exists().returning(|| true);
 
             let manager = Manager::new(processor, path_buf_wrapper);
-            let result = manager.build_registry(FileName::from("test_file"));
+            let result = manager.build_registry(
+                FileName::from("test_file"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
 
             assert!(result.is_err());
             assert!(matches!(result, Err(RegistryError::FsError(_))));
@@ -249,7 +591,11 @@ mod tests {
             path_buf_wrapper.expect_exists().returning(|| false);
 
             let manager = Manager::new(processor, path_buf_wrapper);
-            let result = manager.build_registry(FileName::from("test_file"));
+            let result = manager.build_registry(
+                FileName::from("test_file"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
 
             assert!(result.is_err());
             assert!(matches!(result, Err(RegistryError::FsError(_))));
@@ -279,7 +625,8 @@ mod tests {
             path_buf_wrapper.expect_exists().returning(|| true);
 
             let manager = Manager::new(processor, path_buf_wrapper);
-            let result = manager.build_registry(FileName::from("")); // Empty file name
+            let result =
+                manager.build_registry(FileName::from(""), FileVersion::new(), FileLayout::Nested); // Empty file name
             assert!(result.is_err());
             assert!(matches!(result, Err(RegistryError::CoreError(_))));
 
@@ -287,7 +634,7 @@ mod tests {
                 Err(RegistryError::CoreError(err)) => {
                     assert_eq!(
                         err.to_string(),
-                        "[core error] validation error: File name cannot be empty"
+                        "[core error] validation error: name: File name cannot be empty"
                     );
                 }
                 _ => panic!("Expected CoreError"),
@@ -345,16 +692,74 @@ mod tests {
             path_buf_wrapper.expect_exists().returning(|| true);
 
             let manager = Manager::new(processor, path_buf_wrapper);
-            let result =
-                manager.update_registry(FileName::from("test_file"), FileVersion::from("1.0.0"));
+            let result = manager.update_registry(
+                FileName::from("test_file"),
+                FileVersion::from("1.0.0"),
+                FileLayout::Nested,
+            );
+            // the version was registered already
+            assert!(!result.unwrap());
+        }
+
+        #[test]
+        fn test_update_registry_drops_emptied_files() {
+            let temp_dir_object = tempfile::Builder::new().prefix("output").tempdir().unwrap();
+
+            let temp_dir_path_buf = temp_dir_object.path().to_path_buf();
+            let temp_file_path_buf = temp_dir_path_buf.join("registry.json");
+            let _ = File::create(temp_file_path_buf.clone()).unwrap();
+
+            let mut emptied_file = FileItem::new(FileName::from("emptied_file"));
+            emptied_file.versions.clear();
+
+            let mut registry = Registry::new(Directory::from("output"));
+            registry.add_file(FileItem::new(FileName::from("test_file")));
+            registry.add_file(emptied_file);
+
+            let mut expected_registry = Registry::new(Directory::from("output"));
+            let mut expected_file_item =
+                FileItem::new(FileName::from("test_file")).with_updated_at(fixed_clock());
+            expected_file_item.update(FileVersion::from("1.0.0"));
+            expected_registry.add_file(expected_file_item);
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_parse()
+                .returning(move |_| Ok(registry.clone()));
+
+            processor
+                .expect_build()
+                .with(eq(temp_file_path_buf), eq(expected_registry))
+                .returning(|_, _| Ok(()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+
+            path_buf_wrapper.expect_exists().returning(|| true);
+
+            let manager = Manager::new(processor, path_buf_wrapper).with_clock(fixed_clock);
+            let result = manager.update_registry(
+                FileName::from("test_file"),
+                FileVersion::from("1.0.0"),
+                FileLayout::Nested,
+            );
             assert!(result.is_ok());
         }
 
         #[test]
         fn test_update_registry_file_not_exist() {
             let expected_file_path = PathBuf::from("/tmp/output/registry.json");
-            let mut expected_registry = Registry::new(Directory::from("output"));
-            expected_registry.add_file(FileItem::new(FileName::from("test_file")));
+            // a new file gets registered at the requested version, not at the genesis one
+            let expected_registry = Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::from_version(
+                    FileName::from("test_file"),
+                    FileVersion::from("1.0.0"),
+                )
+                .with_updated_at(fixed_clock())],
+            );
 
             let mut processor = MockFakeProcessor::new();
             processor
@@ -374,10 +779,13 @@ mod tests {
 
             path_buf_wrapper.expect_exists().returning(|| true);
 
-            let manager = Manager::new(processor, path_buf_wrapper);
-            let result =
-                manager.update_registry(FileName::from("test_file"), FileVersion::from("1.0.0"));
-            assert!(result.is_ok());
+            let manager = Manager::new(processor, path_buf_wrapper).with_clock(fixed_clock);
+            let result = manager.update_registry(
+                FileName::from("test_file"),
+                FileVersion::from("1.0.0"),
+                FileLayout::Nested,
+            );
+            assert!(result.unwrap());
         }
 
         mod test_update_registry_validation {
@@ -412,6 +820,7 @@ mod tests {
                 let result = manager.update_registry(
                     FileName::from("test_file"),
                     FileVersion::from("invalid_version"),
+                    FileLayout::Nested,
                 );
 
                 assert!(result.is_err());
@@ -421,7 +830,7 @@ mod tests {
                     Err(RegistryError::CoreError(err)) => {
                         assert_eq!(
                             err.to_string(),
-                            "[core error] validation error: File version can only contain digit characters & dots"
+                            "[core error] validation error: version: File version can only contain digit characters & dots"
                         );
                     }
                     _ => panic!("Expected CoreError"),
@@ -429,4 +838,456 @@ mod tests {
             }
         }
     }
+
+    mod test_case_collision {
+        use super::*;
+
+        fn registry() -> Registry {
+            Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::new(FileName::from("Order"))],
+            )
+        }
+
+        #[test]
+        fn test_case_collision_rejected() {
+            let (manager, processor) = memory_manager(registry());
+
+            let result = manager.check_collision(&FileName::from("order"));
+            assert!(matches!(result, Err(RegistryError::NameCollision(_))));
+
+            let result = manager.update_registry(
+                FileName::from("order"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+            match result {
+                Err(RegistryError::NameCollision(msg)) => {
+                    assert_eq!(msg, "order only differs by case from the registered Order")
+                }
+                _ => panic!("Expected NameCollision"),
+            }
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap(), registry());
+        }
+
+        #[test]
+        fn test_case_collision_allowed_when_case_sensitive() {
+            let (manager, processor) = memory_manager(registry());
+            let manager = manager.with_case_sensitive(true);
+
+            assert!(manager.check_collision(&FileName::from("order")).is_ok());
+            let result = manager.update_registry(
+                FileName::from("order"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+            assert!(result.unwrap());
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap().files.len(), 2);
+        }
+    }
+
+    mod test_max_files {
+        use super::*;
+
+        fn registry(names: &[&str]) -> Registry {
+            Registry::from_files(
+                Directory::from("output"),
+                names
+                    .iter()
+                    .map(|name| FileItem::new(FileName::from(*name)))
+                    .collect(),
+            )
+        }
+
+        #[test]
+        fn test_add_file_at_limit() {
+            let (manager, processor) = memory_manager(registry(&["first"]));
+            let manager = manager.with_max_files(Some(2));
+
+            let result = manager.update_registry(
+                FileName::from("second"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+            assert!(result.unwrap());
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap().files.len(), 2);
+        }
+
+        #[test]
+        fn test_add_file_beyond_limit() {
+            let (manager, processor) = memory_manager(registry(&["first", "second"]));
+            let manager = manager.with_max_files(Some(2));
+
+            let result = manager.update_registry(
+                FileName::from("third"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+            assert!(matches!(result, Err(RegistryError::LimitExceeded(_))));
+            assert_eq!(
+                processor.registry(MEMORY_REGISTRY).unwrap(),
+                registry(&["first", "second"])
+            );
+        }
+
+        #[test]
+        fn test_update_existing_file_beyond_limit() {
+            let (manager, processor) = memory_manager(registry(&["first", "second"]));
+            let manager = manager.with_max_files(Some(2));
+
+            let result = manager.update_registry(
+                FileName::from("second"),
+                FileVersion::from("1.0.0"),
+                FileLayout::Nested,
+            );
+            assert!(result.unwrap());
+            let registry = processor.registry(MEMORY_REGISTRY).unwrap();
+            assert_eq!(registry.files.len(), 2);
+            assert!(registry
+                .get_file(&FileName::from("second"))
+                .unwrap()
+                .versions
+                .contains(&FileVersion::from("1.0.0")));
+        }
+    }
+
+    mod test_max_versions {
+        use super::*;
+
+        fn registry() -> Registry {
+            let mut file_item = FileItem::from_version(FileName::from("order"), FileVersion::new());
+            file_item.update(FileVersion::from("0.2.0"));
+            Registry::from_files(Directory::from("output"), vec![file_item])
+        }
+
+        fn versions(processor: &MemoryProcessor, name: &str) -> Option<usize> {
+            processor
+                .registry(MEMORY_REGISTRY)
+                .unwrap()
+                .get_file(&FileName::from(name))
+                .map(|file_item| file_item.versions.len())
+        }
+
+        #[test]
+        fn test_add_version_at_limit() {
+            let (manager, processor) = memory_manager(registry());
+            let manager = manager.with_max_versions(Some(3));
+
+            let version = FileVersion::from("0.3.0");
+            let file = FileName::from("order");
+            assert!(manager.check_capacity(&file, &version).is_ok());
+            assert!(manager
+                .update_registry(file, version, FileLayout::Nested)
+                .unwrap());
+            assert_eq!(versions(&processor, "order"), Some(3));
+        }
+
+        #[test]
+        fn test_add_version_beyond_limit() {
+            let (manager, processor) = memory_manager(registry());
+            let manager = manager.with_max_versions(Some(2));
+
+            let version = FileVersion::from("0.3.0");
+            let file = FileName::from("order");
+            assert!(matches!(
+                manager.check_capacity(&file, &version),
+                Err(RegistryError::LimitExceeded(_))
+            ));
+            match manager.update_registry(file, version, FileLayout::Nested) {
+                Err(RegistryError::LimitExceeded(msg)) => assert_eq!(
+                    msg,
+                    "order already holds 2 of at most 2 versions, remove old versions before adding 0.3.0"
+                ),
+                _ => panic!("Expected LimitExceeded"),
+            }
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap(), registry());
+        }
+
+        #[test]
+        fn test_existing_version_beyond_limit() {
+            let (manager, processor) = memory_manager(registry());
+            let manager = manager.with_max_versions(Some(1));
+
+            let version = FileVersion::from("0.2.0");
+            let file = FileName::from("order");
+            assert!(manager.check_capacity(&file, &version).is_ok());
+            assert!(!manager
+                .update_registry(file, version, FileLayout::Nested)
+                .unwrap());
+            assert_eq!(versions(&processor, "order"), Some(2));
+        }
+
+        #[test]
+        fn test_new_file_ignores_version_limit() {
+            let (manager, processor) = memory_manager(registry());
+            let manager = manager.with_max_versions(Some(1));
+
+            let result = manager.update_registry(
+                FileName::from("invoice"),
+                FileVersion::new(),
+                FileLayout::Nested,
+            );
+            assert!(result.unwrap());
+            assert_eq!(versions(&processor, "invoice"), Some(1));
+        }
+    }
+
+    mod test_set_domain {
+        use super::*;
+
+        fn registry() -> Registry {
+            Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::new(FileName::from("order"))],
+            )
+        }
+
+        #[test]
+        fn test_set_domain() {
+            let (manager, processor) = memory_manager(registry());
+            let result = manager.set_domain(&FileName::from("order"), "billing".to_string());
+            assert!(result.unwrap());
+
+            let registry = processor.registry(MEMORY_REGISTRY).unwrap();
+            assert_eq!(registry.files[0].domain.as_deref(), Some("billing"));
+        }
+
+        #[test]
+        fn test_set_domain_of_unknown_file() {
+            let (manager, processor) = memory_manager(registry());
+            let result = manager.set_domain(&FileName::from("invoice"), "billing".to_string());
+            assert!(!result.unwrap());
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap(), registry());
+        }
+
+        #[test]
+        fn test_set_empty_domain() {
+            let (manager, processor) = memory_manager(registry());
+            let result = manager.set_domain(&FileName::from("order"), " ".to_string());
+            assert!(matches!(result, Err(RegistryError::CoreError(_))));
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap(), registry());
+        }
+    }
+
+    mod test_deduplicate {
+        use super::*;
+
+        #[test]
+        fn test_deduplicate() {
+            let mut registry = Registry::new(Directory::from("output"));
+            registry.files = vec![
+                FileItem::new(FileName::from("order")),
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.0.0")),
+            ];
+            let (manager, processor) = memory_manager(registry.clone());
+
+            let duplicates = vec!["duplicate order entry".to_string()];
+            assert_eq!(manager.deduplicate(false).unwrap(), duplicates);
+            assert_eq!(processor.registry(MEMORY_REGISTRY).unwrap(), registry);
+
+            assert_eq!(manager.deduplicate(true).unwrap(), duplicates);
+            let repaired = processor.registry(MEMORY_REGISTRY).unwrap();
+            assert_eq!(
+                repaired
+                    .get_file(&FileName::from("order"))
+                    .unwrap()
+                    .versions,
+                vec![FileVersion::new(), FileVersion::from("1.0.0")]
+            );
+            assert_eq!(repaired.files.len(), 1);
+            assert!(manager.deduplicate(false).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_repair_drops_emptied_files() {
+            let mut registry = Registry::new(Directory::from("output"));
+            registry.files = vec![
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.0.0")),
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.0.0")),
+                FileItem::from_version(FileName::from("invoice"), FileVersion::from("1.0.0")),
+            ];
+            registry.files[2].versions.clear();
+            let (manager, processor) = memory_manager(registry);
+
+            manager.deduplicate(true).unwrap();
+            let repaired = processor.registry(MEMORY_REGISTRY).unwrap();
+            assert_eq!(repaired.files.len(), 1);
+            assert!(repaired.get_file(&FileName::from("invoice")).is_none());
+
+            // an export is written through the same path
+            let mut registry = repaired;
+            registry.add_file(FileItem::new(FileName::from("invoice")));
+            registry.files[1].versions.clear();
+            let exported = manager
+                .export(Path::new("/memory/backup"), registry)
+                .unwrap();
+            assert_eq!(processor.registry(exported).unwrap().files.len(), 1);
+        }
+
+        #[test]
+        fn test_deduplicate_without_registry() {
+            let manager = Manager::new(MemoryProcessor::new(), MemoryDir::new(MEMORY_DIR));
+            assert!(manager.deduplicate(true).unwrap().is_empty());
+        }
+    }
+
+    mod test_delete_registry_file {
+        use super::*;
+
+        #[test]
+        fn test_delete_present() {
+            let processor = MemoryProcessor::new().with_registry(
+                MEMORY_REGISTRY,
+                Registry::new(Directory::from("businesses")),
+            );
+
+            let manager = Manager::new(processor.clone(), MemoryDir::new(MEMORY_DIR));
+            assert!(manager.delete_registry_file().is_ok());
+            assert!(processor.paths().is_empty());
+        }
+
+        #[test]
+        fn test_delete_absent() {
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_remove().never();
+
+            let temp_dir = tempfile::tempdir().unwrap();
+            let manager = Manager::new(processor, temp_dir.path().to_path_buf());
+            assert!(manager.delete_registry_file().is_ok());
+        }
+
+        #[test]
+        fn test_delete_refused() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let registry_file_path = temp_dir.path().join("registry.json");
+            std::fs::create_dir(&registry_file_path).unwrap();
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_remove()
+                .with(eq(registry_file_path.clone()))
+                .times(1)
+                .returning(|_| {
+                    Err(RegistryError::FsError(Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "not a regular file",
+                    )))
+                });
+
+            let manager = Manager::new(processor, temp_dir.path().to_path_buf());
+            assert!(matches!(
+                manager.delete_registry_file(),
+                Err(RegistryError::FsError(_))
+            ));
+        }
+    }
+
+    mod test_export {
+        use super::*;
+
+        #[test]
+        fn test_export_in_manager_format() {
+            let registry = Registry::new(Directory::from("businesses"));
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_build()
+                .with(
+                    eq(PathBuf::from("/tmp/backup/registry.yaml")),
+                    eq(registry.clone()),
+                )
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let path_buf_wrapper = MockFakePathBufWrapper::new();
+            let manager =
+                Manager::new(processor, path_buf_wrapper).with_format(RegistryFormat::Yaml);
+
+            let path = manager.export(Path::new("/tmp/backup"), registry).unwrap();
+            assert_eq!(path, PathBuf::from("/tmp/backup/registry.yaml"));
+        }
+    }
+
+    mod test_format {
+        use super::*;
+        use std::fs::File;
+
+        #[test]
+        fn test_registry_file_path_per_format() {
+            for format in [RegistryFormat::Json, RegistryFormat::Yaml] {
+                let temp_dir = tempfile::tempdir().unwrap();
+                let temp_dir_path_buf = temp_dir.path().to_path_buf();
+                let registry_file_path = temp_dir_path_buf.join(format.file_name());
+                let _ = File::create(&registry_file_path).unwrap();
+
+                let mut processor = MockFakeProcessor::new();
+                processor
+                    .expect_parse()
+                    .with(eq(registry_file_path))
+                    .times(1)
+                    .returning(|_| Ok(Registry::new(Directory::from("output"))));
+
+                let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+                path_buf_wrapper
+                    .expect_to_path_buf()
+                    .returning(move || temp_dir_path_buf.clone());
+
+                let manager = Manager::new(processor, path_buf_wrapper).with_format(format);
+                assert!(manager.snapshot().unwrap().files.is_empty());
+            }
+        }
+    }
+
+    mod test_snapshot {
+        use super::*;
+        use std::fs::File;
+
+        #[test]
+        fn test_snapshot_present() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+            let _ = File::create(temp_dir_path_buf.join(RegistryFormat::Json.file_name())).unwrap();
+
+            let registry = Registry::from_files(
+                Directory::from("output"),
+                vec![FileItem::new(FileName::from("order"))],
+            );
+            let expected_registry = registry.clone();
+
+            let mut processor = MockFakeProcessor::new();
+            processor
+                .expect_parse()
+                .with(eq(temp_dir_path_buf.join(RegistryFormat::Json.file_name())))
+                .returning(move |_| Ok(registry.clone()));
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+
+            let manager = Manager::new(processor, path_buf_wrapper);
+            assert_eq!(manager.snapshot().unwrap(), expected_registry);
+        }
+
+        #[test]
+        fn test_snapshot_absent() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let temp_dir_path_buf = temp_dir.path().to_path_buf();
+
+            let mut processor = MockFakeProcessor::new();
+            processor.expect_parse().never();
+
+            let mut path_buf_wrapper = MockFakePathBufWrapper::new();
+            path_buf_wrapper
+                .expect_to_path_buf()
+                .returning(move || temp_dir_path_buf.clone());
+            path_buf_wrapper
+                .expect_dir_name()
+                .returning(|| Some("output".to_string()));
+
+            let manager = Manager::new(processor, path_buf_wrapper);
+            let registry = manager.snapshot().unwrap();
+            assert_eq!(registry, Registry::new(Directory::from("output")));
+        }
+    }
 }
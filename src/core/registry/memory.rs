@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::core::registry::types::{
+    Processor, Registry, RegistryError, RegistryInfo, REGISTRY_SCHEMA_VERSION,
+};
+use crate::core::types::PathBufWrapper;
+
+/// `MemoryProcessor` keeps registries in memory by file path, so a [`Manager`] can be
+/// exercised without touching the disk nor mocking every call.
+///
+/// Clones share the same registries, a clone kept by the test sees what the manager wrote.
+///
+/// [`Manager`]: crate::core::registry::manager::Manager
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryProcessor {
+    registries: Rc<RefCell<HashMap<PathBuf, Registry>>>,
+}
+
+impl MemoryProcessor {
+    pub(crate) fn new() -> Self {
+        MemoryProcessor::default()
+    }
+
+    /// `with_registry` seeds a registry at the given file path.
+    pub(crate) fn with_registry(self, file_path: impl Into<PathBuf>, registry: Registry) -> Self {
+        self.seed(file_path, registry);
+        self
+    }
+
+    /// `seed` stores a registry at the given file path, replacing any previous one.
+    pub(crate) fn seed(&self, file_path: impl Into<PathBuf>, registry: Registry) {
+        self.registries
+            .borrow_mut()
+            .insert(file_path.into(), registry);
+    }
+
+    /// `registry` returns a copy of the registry stored at the given file path.
+    pub(crate) fn registry(&self, file_path: impl AsRef<Path>) -> Option<Registry> {
+        self.registries.borrow().get(file_path.as_ref()).cloned()
+    }
+
+    /// `paths` lists the file paths holding a registry, sorted.
+    pub(crate) fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.registries.borrow().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+}
+
+impl Processor for MemoryProcessor {
+    fn build(&self, file_path: PathBuf, registry: Registry) -> Result<(), RegistryError> {
+        self.seed(file_path, registry);
+        Ok(())
+    }
+
+    fn parse(&self, file_path: PathBuf) -> Result<Registry, RegistryError> {
+        self.registry(&file_path).ok_or_else(|| {
+            RegistryError::FsError(Error::new(
+                ErrorKind::NotFound,
+                format!("no registry at {}", file_path.display()),
+            ))
+        })
+    }
+
+    fn inspect(&self, file_path: PathBuf) -> Result<RegistryInfo, RegistryError> {
+        let registry = self.parse(file_path)?;
+        Ok(RegistryInfo::new(
+            REGISTRY_SCHEMA_VERSION,
+            registry.files.len(),
+        ))
+    }
+
+    fn remove(&self, file_path: &Path) -> Result<(), RegistryError> {
+        self.registries
+            .borrow_mut()
+            .remove(file_path)
+            .map(|_| ())
+            .ok_or_else(|| {
+                RegistryError::FsError(Error::new(
+                    ErrorKind::NotFound,
+                    format!("no registry at {}", file_path.display()),
+                ))
+            })
+    }
+
+    fn exists(&self, file_path: &Path) -> bool {
+        self.registries.borrow().contains_key(file_path)
+    }
+}
+
+/// `MemoryDir` is an output directory which always exists, to go along with a
+/// [`MemoryProcessor`].
+#[derive(Debug, Clone)]
+pub(crate) struct MemoryDir(PathBuf);
+
+impl MemoryDir {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        MemoryDir(path.into())
+    }
+}
+
+impl PathBufWrapper for MemoryDir {
+    fn to_path_buf(&self) -> PathBuf {
+        self.0.clone()
+    }
+
+    fn dir_name(&self) -> Option<String> {
+        self.0
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    fn exists(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::registry::manager::Manager;
+    use crate::core::registry::types::{Directory, FileItem, FileLayout, FileName, FileVersion};
+
+    const REGISTRY_PATH: &str = "/memory/businesses/registry.json";
+
+    #[test]
+    fn test_manager_builds_then_updates() {
+        let processor = MemoryProcessor::new();
+        let manager = Manager::new(processor.clone(), MemoryDir::new("/memory/businesses"));
+        assert!(manager.inspect().unwrap().is_none());
+
+        let order = FileName::from("order");
+        for version in ["0.1.0", "0.2.0", "0.1.0"] {
+            manager
+                .update_registry(
+                    order.clone(),
+                    FileVersion::from(version),
+                    FileLayout::Nested,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(processor.paths(), vec![PathBuf::from(REGISTRY_PATH)]);
+        let registry = processor.registry(REGISTRY_PATH).unwrap();
+        assert_eq!(registry.directory(), &Directory::from("businesses"));
+        assert_eq!(
+            registry.get_file(&order).unwrap().versions,
+            vec![FileVersion::from("0.1.0"), FileVersion::from("0.2.0")]
+        );
+        assert_eq!(manager.inspect().unwrap().unwrap().files, 1);
+    }
+
+    #[test]
+    fn test_manager_reads_seeded_registry() {
+        let mut registry = Registry::new(Directory::from("businesses"));
+        registry.add_file(FileItem::from_version(
+            FileName::from("order"),
+            FileVersion::from("1.0.0"),
+        ));
+        let processor = MemoryProcessor::new().with_registry(REGISTRY_PATH, registry.clone());
+
+        let manager =
+            Manager::new(processor, MemoryDir::new("/memory/businesses")).with_max_files(Some(1));
+        assert_eq!(manager.snapshot().unwrap(), registry);
+        assert!(matches!(
+            manager.check_capacity(&FileName::from("invoice"), &FileVersion::new()),
+            Err(RegistryError::LimitExceeded(_))
+        ));
+    }
+}
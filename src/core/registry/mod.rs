@@ -1,2 +1,4 @@
 pub(crate) mod manager;
+#[cfg(test)]
+pub(crate) mod memory;
 pub(crate) mod types;
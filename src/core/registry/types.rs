@@ -1,5 +1,9 @@
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -7,7 +11,15 @@ use crate::core::types::{CoreError, ToJSON, Validator};
 
 pub(crate) const REGISTRY_VERSION_GENESIS: &str = "0.1.0";
 
-pub(crate) const REGISTRY_FILE_NAME: &str = "registry.json";
+/// Version of the on-disk registry layout, written along with every registry. Registries
+/// written before it was introduced carry none and count as version 0.
+pub(crate) const REGISTRY_SCHEMA_VERSION: u32 = 1;
+
+/// The registry file name without its extension, which comes from the [`RegistryFormat`].
+pub(crate) const REGISTRY_FILE_BASE_NAME: &str = "registry";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
 
 #[derive(Error, Debug)]
 pub(crate) enum RegistryError {
@@ -16,9 +28,18 @@ pub(crate) enum RegistryError {
 
     #[error("[registry error] core error: {0}")]
     CoreError(#[from] CoreError),
+
+    #[error("[registry error] limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("[registry error] name collision: {0}")]
+    NameCollision(String),
+
+    #[error("[registry error] checksum mismatch, the registry was modified outside of ddai: {0}")]
+    ChecksumMismatch(String),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) struct FileName(String);
 
 impl FileName {
@@ -39,7 +60,15 @@ impl From<&str> for FileName {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// `VersionPart` names one of the three components of a [`FileVersion`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum VersionPart {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct FileVersion(String);
 
 impl FileVersion {
@@ -47,47 +76,113 @@ impl FileVersion {
         FileVersion::from(REGISTRY_VERSION_GENESIS)
     }
 
-    #[allow(dead_code)]
     pub fn as_str(&self) -> &str {
         &self.0
     }
 
-    pub fn to_string(&self) -> String {
-        self.0.to_owned()
+    /// Returns the major, minor and patch parts, `None` unless the version is exactly three
+    /// numeric parts.
+    fn semver(&self) -> Option<(u32, u32, u32)> {
+        let parts = self
+            .0
+            .split('.')
+            .map(|part| match part.bytes().all(|b| b.is_ascii_digit()) {
+                true => part.parse::<u32>().ok(),
+                false => None,
+            })
+            .collect::<Option<Vec<u32>>>()?;
+
+        match parts[..] {
+            [major, minor, patch] => Some((major, minor, patch)),
+            _ => None,
+        }
+    }
+
+    fn numeric_parts(&self) -> Vec<u32> {
+        self.0
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or_default())
+            .collect()
+    }
+
+    /// Returns the next version for the given part, resetting the parts after it.
+    ///
+    /// The current version must be valid, and the incremented part must stay within 255.
+    pub(crate) fn increment(&self, part: VersionPart) -> Result<FileVersion, CoreError> {
+        self.validate()?;
+
+        let parts = self.numeric_parts();
+
+        let (major, minor, patch) = match part {
+            VersionPart::Major => (parts[0] + 1, 0, 0),
+            VersionPart::Minor => (parts[0], parts[1] + 1, 0),
+            VersionPart::Patch => (parts[0], parts[1], parts[2] + 1),
+        };
+
+        let version = FileVersion::from(format!("{}.{}.{}", major, minor, patch));
+        version.validate()?;
+        Ok(version)
+    }
+}
+
+/// Versions compare by major, then minor, then patch, so `1.2.10` comes after `1.2.9`.
+///
+/// Those which don't parse sort below every parsed one, and between themselves by their
+/// numeric parts. Ties are broken by the raw string to stay consistent with equality.
+impl Ord for FileVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.semver()
+            .cmp(&other.semver())
+            .then_with(|| self.numeric_parts().cmp(&other.numeric_parts()))
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for FileVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for FileVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 impl Validator for FileVersion {
     fn validate(&self) -> Result<(), CoreError> {
         if self.0.is_empty() {
-            return Err(CoreError::ValidationError(
-                "File version cannot be empty".to_string(),
-            ));
+            return Err(CoreError::field("version", "File version cannot be empty"));
         }
 
         if !self.0.chars().all(|c| c.is_ascii_digit() || c == '.') {
-            return Err(CoreError::ValidationError(
-                "File version can only contain digit characters & dots".to_string(),
+            return Err(CoreError::field(
+                "version",
+                "File version can only contain digit characters & dots",
             ));
         }
 
         let parts: Vec<&str> = self.0.split('.').collect();
         if parts.is_empty() || parts.iter().any(|part| part.is_empty()) {
-            return Err(CoreError::ValidationError(
-                "File version must contain at least one non-empty part".to_string(),
+            return Err(CoreError::field(
+                "version",
+                "File version must contain at least one non-empty part",
             ));
         }
 
         if parts.len() != 3 {
-            return Err(CoreError::ValidationError(
-                "File version can have at most three parts".to_string(),
+            return Err(CoreError::field(
+                "version",
+                "File version can have at most three parts",
             ));
         }
 
         for part in parts.clone() {
-            if let Err(_) = part.parse::<u32>() {
-                return Err(CoreError::ValidationError(
-                    "Each part of the file version must be a valid unsigned integer".to_string(),
+            if part.parse::<u32>().is_err() {
+                return Err(CoreError::field(
+                    "version",
+                    "Each part of the file version must be a valid unsigned integer",
                 ));
             }
         }
@@ -96,17 +191,16 @@ impl Validator for FileVersion {
             && parts[1].parse::<u32>().unwrap() == 0
             && parts[2].parse::<u32>().unwrap() == 0
         {
-            return Err(CoreError::ValidationError(
-                "File version cannot be zero".to_string(),
-            ));
+            return Err(CoreError::field("version", "File version cannot be zero"));
         }
 
         if parts[0].parse::<u32>().unwrap() > 255
             || parts[1].parse::<u32>().unwrap() > 255
             || parts[2].parse::<u32>().unwrap() > 255
         {
-            return Err(CoreError::ValidationError(
-                "Each part of the file version must be between 0 and 255".to_string(),
+            return Err(CoreError::field(
+                "version",
+                "Each part of the file version must be between 0 and 255",
             ));
         }
         Ok(())
@@ -128,6 +222,30 @@ impl From<&str> for FileVersion {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub(crate) struct Directory(String);
 
+impl Directory {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Validator for Directory {
+    fn validate(&self) -> Result<(), CoreError> {
+        if self.0.is_empty() {
+            return Err(CoreError::field("directory", "Directory cannot be empty"));
+        }
+
+        if self.0.contains(['/', '\\']) {
+            return Err(CoreError::field(
+                "directory",
+                "Directory must be a single path component",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 impl From<String> for Directory {
     fn from(dir: String) -> Self {
         Directory(dir)
@@ -140,70 +258,253 @@ impl From<&str> for Directory {
     }
 }
 
+/// `RegistryFormat` is the serialization format of the registry file.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RegistryFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl RegistryFormat {
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            RegistryFormat::Json => "json",
+            RegistryFormat::Yaml => "yaml",
+        }
+    }
+
+    /// Returns the registry file name in this format, e.g. `registry.json`.
+    pub(crate) fn file_name(&self) -> String {
+        format!("{}.{}", REGISTRY_FILE_BASE_NAME, self.extension())
+    }
+
+    /// Guesses the format from a file extension, `yml` is accepted as well.
+    pub(crate) fn from_path(path: &Path) -> Option<RegistryFormat> {
+        match path.extension()?.to_str()? {
+            "json" => Some(RegistryFormat::Json),
+            "yaml" | "yml" => Some(RegistryFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// `FileLayout` tells where the versions of a file are placed inside the registry directory.
+///
+/// Entries written before the layout was recorded default to [`FileLayout::Nested`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FileLayout {
+    /// One directory per file, holding `{version}.md`
+    #[default]
+    Nested,
+
+    /// `{name}-{version}.md` directly under the registry directory
+    Flat,
+}
+
+/// `FileLocation` tells where the versions of a file are stored, with their layout inside
+/// either a directory of their own or, by default, the registry directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FileLocation {
+    pub(crate) layout: FileLayout,
+    pub(crate) dir: Option<PathBuf>,
+}
+
+impl FileLocation {
+    pub(crate) fn with_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.dir = dir;
+        self
+    }
+}
+
+impl From<FileLayout> for FileLocation {
+    fn from(layout: FileLayout) -> Self {
+        FileLocation { layout, dir: None }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub(crate) struct FileItem {
     pub(crate) name: FileName,
     pub(crate) versions: Vec<FileVersion>,
+
+    #[serde(default)]
+    pub(crate) layout: FileLayout,
+
+    /// The directory holding the versions when it's not the registry directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dir: Option<PathBuf>,
+
+    /// The domain grouping the file, e.g. `billing`, absent for an ungrouped file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) domain: Option<String>,
+
+    /// When a version was last added, absent until a version is registered with it recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) updated_at: Option<DateTime<Utc>>,
 }
 
 impl FileItem {
+    #[allow(dead_code)]
     pub(crate) fn new(name: FileName) -> Self {
-        let mut versions = Vec::<FileVersion>::new();
-        versions.push(FileVersion::new());
+        FileItem {
+            name,
+            versions: vec![FileVersion::new()],
+            layout: FileLayout::default(),
+            dir: None,
+            domain: None,
+            updated_at: None,
+        }
+    }
 
+    /// Builds a file item holding only the given version, used when a file is first
+    /// registered at a version other than the genesis one.
+    pub(crate) fn from_version(name: FileName, version: FileVersion) -> Self {
         FileItem {
             name,
-            versions: versions,
+            versions: vec![version],
+            layout: FileLayout::default(),
+            dir: None,
+            domain: None,
+            updated_at: None,
         }
     }
 
-    #[allow(dead_code)]
+    #[cfg(test)]
+    pub(crate) fn with_layout(mut self, layout: FileLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Stores the versions at the given location, see [`FileItem::location`].
+    pub(crate) fn with_location(mut self, location: FileLocation) -> Self {
+        self.layout = location.layout;
+        self.dir = location.dir;
+        self
+    }
+
+    /// Records when a version was last added.
+    pub(crate) fn with_updated_at(mut self, updated_at: DateTime<Utc>) -> Self {
+        self.updated_at = Some(updated_at);
+        self
+    }
+
+    /// Returns where the versions of the file are stored.
+    pub(crate) fn location(&self) -> FileLocation {
+        FileLocation::from(self.layout).with_dir(self.dir.clone())
+    }
+
+    /// Returns the highest version, whatever the order the versions were added in.
     pub(crate) fn get_last_version(&self) -> Option<FileVersion> {
-        self.versions.last().and_then(|val| Some(val.to_owned()))
+        self.versions.iter().max().cloned()
+    }
+
+    /// Returns the highest version starting with the given dotted prefix.
+    ///
+    /// The prefix matches whole components only, so `1.2` selects `1.2.5` but not `1.20.0`.
+    pub(crate) fn latest_with_prefix(&self, prefix: &str) -> Option<FileVersion> {
+        let prefix: Vec<&str> = prefix
+            .trim_end_matches('.')
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        self.versions
+            .iter()
+            .filter(|version| {
+                version
+                    .as_str()
+                    .split('.')
+                    .collect::<Vec<_>>()
+                    .starts_with(&prefix)
+            })
+            .max()
+            .cloned()
     }
 
-    pub(crate) fn update(&mut self, version: FileVersion) {
-        if !self.versions.contains(&version) {
-            self.versions.push(version);
+    /// Adds the version unless the file already holds it, returns whether it was added.
+    pub(crate) fn update(&mut self, version: FileVersion) -> bool {
+        if self.versions.contains(&version) {
+            return false;
         }
+
+        self.versions.push(version);
+        true
     }
 }
 
+impl ToJSON for FileItem {}
+
 impl From<&FileItem> for FileItem {
     fn from(file_item: &FileItem) -> Self {
         file_item.clone()
     }
 }
 
-impl Validator for FileItem {
-    fn validate(&self) -> Result<(), CoreError> {
+impl FileItem {
+    /// `problems` lists every invalid part of the item in order, each along with the
+    /// version it concerns when there is one.
+    pub(crate) fn problems(&self) -> Vec<(Option<&FileVersion>, CoreError)> {
+        let mut problems = vec![];
         if self.name.as_str().is_empty() {
-            return Err(CoreError::ValidationError(
-                "File name cannot be empty".to_string(),
-            ));
+            problems.push((None, CoreError::field("name", "File name cannot be empty")));
         }
 
         if self.versions.is_empty() {
-            return Err(CoreError::ValidationError(
-                "File must have at least one version".to_string(),
+            problems.push((
+                None,
+                CoreError::field("versions", "File must have at least one version"),
             ));
         }
 
         for version in &self.versions {
-            version.validate()?;
+            if let Err(err) = version.validate() {
+                problems.push((Some(version), err));
+            }
         }
 
-        Ok(())
+        if self
+            .domain
+            .as_ref()
+            .is_some_and(|domain| domain.trim().is_empty())
+        {
+            problems.push((
+                None,
+                CoreError::field("domain", "File domain cannot be empty"),
+            ));
+        }
+
+        problems
     }
 }
 
+impl Validator for FileItem {
+    fn validate(&self) -> Result<(), CoreError> {
+        match self.problems().into_iter().next() {
+            Some((_, err)) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `RegistryProblem` is an invalid part of a registry, along with where it was found.
+#[derive(Debug)]
+pub(crate) struct RegistryProblem {
+    /// Where the problem is, `directory`, a file name or a file name and version. A file
+    /// without a name is located by its position, e.g. `files[2]`
+    pub(crate) location: String,
+    pub(crate) error: CoreError,
+}
+
 /// `Registry` struct represents a collection of files and their versions within a specific directory.
 ///
 /// It contains a directory path and a list of file items, each with its name and associated versions.
 /// It will be saved as a JSON file in the specified directory.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub(crate) struct Registry {
-    pub(crate) directory: Directory,
+    directory: Directory,
     pub(crate) files: Vec<FileItem>,
 }
 
@@ -215,6 +516,60 @@ impl Registry {
         }
     }
 
+    /// Builds a registry holding the given files.
+    ///
+    /// Files sharing a name are deduplicated the same way [`Registry::add_file`] does,
+    /// so the last one wins.
+    pub(crate) fn from_files(directory: Directory, files: Vec<FileItem>) -> Self {
+        let mut registry = Registry::new(directory);
+        files.into_iter().for_each(|file| registry.add_file(file));
+        registry
+    }
+
+    /// Returns the label of the directory the registry belongs to.
+    #[cfg(test)]
+    pub(crate) fn directory(&self) -> &Directory {
+        &self.directory
+    }
+
+    /// Replaces the directory label, e.g. when the businesses directory gets renamed.
+    ///
+    /// The label is validated first and left untouched when invalid.
+    #[cfg(test)]
+    pub(crate) fn set_directory(&mut self, directory: Directory) -> Result<(), CoreError> {
+        directory.validate()?;
+        self.directory = directory;
+        Ok(())
+    }
+
+    /// Renders a compact summary of the registry, one `{name} {latest version}` line per file.
+    ///
+    /// Lines are dropped once the summary would exceed `max_chars`, a trailing note then
+    /// tells how many files were left out.
+    pub(crate) fn summary(&self, max_chars: usize) -> String {
+        let mut lines: Vec<String> = vec![];
+        let mut size = 0;
+
+        for (index, file) in self.files.iter().enumerate() {
+            let line = match file.versions.iter().max() {
+                Some(version) => format!("{} {}", file.name.as_str(), version),
+                None => file.name.as_str().to_string(),
+            };
+
+            size += line.chars().count() + 1;
+            if size > max_chars {
+                lines.push(format!(
+                    "... {} more files omitted",
+                    self.files.len() - index
+                ));
+                break;
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
     pub(crate) fn remove_file(&mut self, file_name: &FileName) {
         self.files.retain(|file| &file.name != file_name);
     }
@@ -236,13 +591,199 @@ impl Registry {
         }
     }
 
+    /// Adds a version to a registered file.
+    ///
+    /// Returns `true` when the version was newly added, `false` when the file already held it
+    /// or when no file with that name is registered.
+    pub(crate) fn upsert_version(&mut self, name: &FileName, version: FileVersion) -> bool {
+        self.files
+            .iter_mut()
+            .find(|file| &file.name == name)
+            .is_some_and(|file| file.update(version))
+    }
+
+    /// Records when a version was last added to a registered file.
+    pub(crate) fn touch(&mut self, name: &FileName, updated_at: DateTime<Utc>) {
+        if let Some(file) = self.files.iter_mut().find(|file| &file.name == name) {
+            file.updated_at = Some(updated_at);
+        }
+    }
+
+    /// Returns the file a version was most recently added to.
+    ///
+    /// Files without a recorded time are skipped, so it's `None` when no file has one, e.g.
+    /// in a registry written before the times were recorded.
+    pub(crate) fn latest_updated(&self) -> Option<&FileItem> {
+        self.files
+            .iter()
+            .filter(|file| file.updated_at.is_some())
+            .max_by_key(|file| file.updated_at)
+    }
+
+    /// Groups a registered file under the given domain.
+    ///
+    /// Returns `true` when the domain changed, `false` when the file already was in that
+    /// domain or when no file with that name is registered.
+    pub(crate) fn set_domain(&mut self, name: &FileName, domain: String) -> bool {
+        match self.files.iter_mut().find(|file| &file.name == name) {
+            Some(file) if file.domain.as_ref() != Some(&domain) => {
+                file.domain = Some(domain);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn get_file(&self, file_name: &FileName) -> Option<&FileItem> {
         self.files.iter().find(|file| &file.name == file_name)
     }
+
+    /// Collapses the file items sharing a name into the first one, merging their versions,
+    /// and drops the versions repeated within an item, e.g. after a manual edit.
+    ///
+    /// The first item keeps its location, a domain or update time it lacks is taken from
+    /// its duplicates. It returns a description of every duplicate, empty when there was none.
+    pub(crate) fn deduplicate(&mut self) -> Vec<String> {
+        let mut duplicates = vec![];
+        let mut files: Vec<FileItem> = Vec::with_capacity(self.files.len());
+
+        for file in self.files.drain(..) {
+            let Some(kept) = files.iter_mut().find(|kept| kept.name == file.name) else {
+                files.push(file);
+                continue;
+            };
+
+            duplicates.push(format!("duplicate {} entry", file.name.as_str()));
+            kept.versions.extend(file.versions);
+            kept.domain = kept.domain.take().or(file.domain);
+            kept.updated_at = kept.updated_at.max(file.updated_at);
+        }
+
+        for file in files.iter_mut() {
+            let mut versions: Vec<FileVersion> = Vec::with_capacity(file.versions.len());
+            for version in file.versions.drain(..) {
+                if versions.contains(&version) {
+                    duplicates.push(format!(
+                        "duplicate {} {} version",
+                        file.name.as_str(),
+                        version
+                    ));
+                } else {
+                    versions.push(version);
+                }
+            }
+            file.versions = versions;
+        }
+
+        self.files = files;
+        duplicates
+    }
+
+    /// Drops every file item that no longer holds any version.
+    ///
+    /// Such entries carry no meaning, so they are removed before the registry gets persisted.
+    pub(crate) fn gc(&mut self) {
+        self.files.retain(|file| !file.versions.is_empty());
+    }
+
+    /// Returns a file whose name only differs by case from the given one.
+    ///
+    /// Such names map to the same directory on case-insensitive filesystems.
+    pub(crate) fn case_collision(&self, name: &FileName) -> Option<&FileItem> {
+        let lowercase = name.as_str().to_lowercase();
+        self.files
+            .iter()
+            .find(|file| &file.name != name && file.name.as_str().to_lowercase() == lowercase)
+    }
+
+    /// Puts the registry in canonical order: files by name, and the versions of each file
+    /// from the oldest to the newest.
+    ///
+    /// Two registries holding the same entries compare equal once both are sorted.
+    pub(crate) fn sort(&mut self) {
+        self.files.sort_by(|a, b| a.name.cmp(&b.name));
+        self.files.iter_mut().for_each(|file| file.versions.sort());
+    }
+
+    /// Validates the directory, every file and every version of the registry, and returns
+    /// all of the problems found instead of stopping at the first one.
+    pub(crate) fn problems(&self) -> Vec<RegistryProblem> {
+        let mut problems = vec![];
+        if let Err(error) = self.directory.validate() {
+            problems.push(RegistryProblem {
+                location: "directory".to_string(),
+                error,
+            });
+        }
+
+        for (index, file_item) in self.files.iter().enumerate() {
+            let name = match file_item.name.as_str() {
+                "" => format!("files[{}]", index),
+                name => name.to_string(),
+            };
+            for (version, error) in file_item.problems() {
+                let location = match version {
+                    Some(version) => format!("{} {}", name, version),
+                    None => name.clone(),
+                };
+                problems.push(RegistryProblem { location, error });
+            }
+        }
+
+        problems
+    }
+
+    /// Returns how many files hold each number of versions, keyed by the version count.
+    pub(crate) fn version_histogram(&self) -> BTreeMap<usize, usize> {
+        self.files
+            .iter()
+            .fold(BTreeMap::new(), |mut histogram, file| {
+                *histogram.entry(file.versions.len()).or_insert(0) += 1;
+                histogram
+            })
+    }
+
+    /// Returns a checksum of the registry content, computed over its canonical JSON form
+    /// so it doesn't depend on the order of the files and versions.
+    ///
+    /// It is a 64-bit FNV-1a hash meant to detect corruption and manual edits, it is not
+    /// a cryptographic signature.
+    pub(crate) fn checksum(&self) -> Result<String, CoreError> {
+        let mut canonical = self.clone();
+        canonical.sort();
+        let content = serde_json::to_vec(&canonical).map_err(CoreError::JSONError)?;
+
+        let hash = content.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        });
+        Ok(format!("{:016x}", hash))
+    }
 }
 
 impl ToJSON for Registry {}
 
+/// `RegistryInfo` describes a registry file as found on disk, before any migration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct RegistryInfo {
+    pub(crate) schema_version: u32,
+    pub(crate) files: usize,
+
+    /// Whether the next write upgrades the file to [`REGISTRY_SCHEMA_VERSION`]
+    pub(crate) migration_pending: bool,
+}
+
+impl RegistryInfo {
+    pub(crate) fn new(schema_version: u32, files: usize) -> Self {
+        RegistryInfo {
+            schema_version,
+            files,
+            migration_pending: schema_version < REGISTRY_SCHEMA_VERSION,
+        }
+    }
+}
+
+impl ToJSON for RegistryInfo {}
+
 /// This trait defines the interface for processing registry files.
 ///
 /// It includes methods for building a registry from a file path and a registry object,
@@ -250,15 +791,91 @@ impl ToJSON for Registry {}
 pub(crate) trait Processor {
     fn build(&self, file_path: PathBuf, registry: Registry) -> Result<(), RegistryError>;
     fn parse(&self, file_path: PathBuf) -> Result<Registry, RegistryError>;
+
+    /// Reads the raw registry file, without migrating nor verifying it.
+    fn inspect(&self, file_path: PathBuf) -> Result<RegistryInfo, RegistryError>;
+
+    /// Removes the registry file, refusing to remove anything that isn't a regular file.
+    #[cfg(test)]
+    fn remove(&self, file_path: &Path) -> Result<(), RegistryError>;
+
+    /// Whether a registry file exists at the given path, on disk unless the processor
+    /// keeps its registries elsewhere.
+    fn exists(&self, file_path: &Path) -> bool {
+        file_path.exists()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    mod test_registry_format {
+        use super::*;
+
+        #[test]
+        fn test_registry_format_file_name() {
+            assert_eq!(RegistryFormat::default().file_name(), "registry.json");
+            assert_eq!(RegistryFormat::Yaml.file_name(), "registry.yaml");
+        }
+
+        #[test]
+        fn test_registry_format_from_path() {
+            for format in [RegistryFormat::Json, RegistryFormat::Yaml] {
+                let path = PathBuf::from("businesses").join(format.file_name());
+                assert_eq!(RegistryFormat::from_path(&path), Some(format));
+            }
+
+            let path = Path::new("registry.yml");
+            assert_eq!(RegistryFormat::from_path(path), Some(RegistryFormat::Yaml));
+            assert_eq!(RegistryFormat::from_path(Path::new("registry")), None);
+        }
+    }
+
     mod test_file_version {
         use super::*;
 
+        #[test]
+        fn test_file_version_ordering() {
+            let mut versions: Vec<FileVersion> = ["1.10.0", "1.2.10", "0.1.0", "1.2.9", "2.0.0"]
+                .into_iter()
+                .map(FileVersion::from)
+                .collect();
+            versions.sort();
+
+            let sorted: Vec<&str> = versions.iter().map(|v| v.as_str()).collect();
+            assert_eq!(sorted, vec!["0.1.0", "1.2.9", "1.2.10", "1.10.0", "2.0.0"]);
+            assert!(FileVersion::from("1.0.0") < FileVersion::from("1.0.1"));
+        }
+
+        #[test]
+        fn test_file_version_ordering_equal_versions() {
+            let version = FileVersion::from("1.5.0");
+            assert_eq!(version.cmp(&FileVersion::from("1.5.0")), Ordering::Equal);
+            assert_eq!(
+                [FileVersion::from("1.5.0"), FileVersion::from("1.5.0")]
+                    .iter()
+                    .max(),
+                Some(&version)
+            );
+        }
+
+        #[test]
+        fn test_file_version_ordering_invalid_below_valid() {
+            let mut versions: Vec<FileVersion> =
+                ["1.0.0", "9.x.0", "", "0.0.1", "10.0", "2.0.0.1", "v3.0.0"]
+                    .into_iter()
+                    .map(FileVersion::from)
+                    .collect();
+            versions.sort();
+
+            let sorted: Vec<&str> = versions.iter().map(|v| v.as_str()).collect();
+            assert_eq!(
+                sorted,
+                vec!["", "v3.0.0", "2.0.0.1", "9.x.0", "10.0", "0.0.1", "1.0.0"]
+            );
+        }
+
         #[test]
         fn test_file_version_validation() {
             let valid_version = FileVersion::from("1.0.0");
@@ -280,6 +897,45 @@ mod tests {
             assert!(invalid_version_out_of_range.validate().is_err());
         }
 
+        #[test]
+        fn test_file_version_increment() {
+            let version = FileVersion::from("1.2.3");
+            assert_eq!(
+                version.increment(VersionPart::Major).unwrap(),
+                FileVersion::from("2.0.0")
+            );
+            assert_eq!(
+                version.increment(VersionPart::Minor).unwrap(),
+                FileVersion::from("1.3.0")
+            );
+            assert_eq!(
+                version.increment(VersionPart::Patch).unwrap(),
+                FileVersion::from("1.2.4")
+            );
+        }
+
+        #[test]
+        fn test_file_version_increment_overflow() {
+            assert!(FileVersion::from("255.0.0")
+                .increment(VersionPart::Major)
+                .is_err());
+            assert!(FileVersion::from("1.255.9")
+                .increment(VersionPart::Minor)
+                .is_err());
+            assert!(FileVersion::from("1.2.255")
+                .increment(VersionPart::Patch)
+                .is_err());
+            assert_eq!(
+                FileVersion::from("1.2.255")
+                    .increment(VersionPart::Minor)
+                    .unwrap(),
+                FileVersion::from("1.3.0")
+            );
+            assert!(FileVersion::from("1.2")
+                .increment(VersionPart::Patch)
+                .is_err());
+        }
+
         #[test]
         fn test_file_version_to_string() {
             let version = FileVersion::from("1.0.0");
@@ -312,6 +968,68 @@ mod tests {
     mod test_file_item {
         use super::*;
 
+        #[test]
+        fn test_get_last_version_out_of_order() {
+            let mut file_item =
+                FileItem::from_version(FileName::from("order"), FileVersion::from("2.0.0"));
+            file_item.update(FileVersion::from("1.5.0"));
+            assert_eq!(file_item.versions.last(), Some(&FileVersion::from("1.5.0")));
+            assert_eq!(
+                file_item.get_last_version(),
+                Some(FileVersion::from("2.0.0"))
+            );
+
+            // a hand-edited version which doesn't parse is never the latest
+            file_item.versions.push(FileVersion::from("9.x.0"));
+            assert_eq!(
+                file_item.get_last_version(),
+                Some(FileVersion::from("2.0.0"))
+            );
+
+            assert!(FileItem {
+                versions: vec![],
+                ..file_item
+            }
+            .get_last_version()
+            .is_none());
+        }
+
+        #[test]
+        fn test_file_item_to_json() {
+            let mut file_item = FileItem::new(FileName::from("order"));
+            file_item.update(FileVersion::from("1.0.0"));
+
+            let json: serde_json::Value =
+                serde_json::from_str(&file_item.to_json().unwrap()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!({
+                    "name": "order",
+                    "versions": ["0.1.0", "1.0.0"],
+                    "layout": "nested"
+                })
+            );
+        }
+
+        #[test]
+        fn test_file_item_domain() {
+            let json = r#"{"name":"order","versions":["0.1.0"]}"#;
+            let file_item: FileItem = serde_json::from_str(json).unwrap();
+            assert!(file_item.domain.is_none());
+            assert!(!file_item.to_json().unwrap().contains("domain"));
+
+            let mut registry = Registry::from_files(Directory::from("output"), vec![file_item]);
+            let name = FileName::from("order");
+            assert!(registry.set_domain(&name, "billing".to_string()));
+            assert!(!registry.set_domain(&name, "billing".to_string()));
+            assert!(!registry.set_domain(&FileName::from("invoice"), "billing".to_string()));
+            assert_eq!(registry.files[0].domain.as_deref(), Some("billing"));
+
+            let mut file_item = registry.files[0].clone();
+            file_item.domain = Some(" ".to_string());
+            assert!(file_item.validate().is_err());
+        }
+
         #[test]
         fn test_file_item_validation() {
             let valid_file = FileItem::new(FileName::from("test_file"));
@@ -320,21 +1038,61 @@ mod tests {
             let invalid_file_empty_name = FileItem {
                 name: FileName::from(""),
                 versions: vec![FileVersion::new()],
+                layout: FileLayout::Nested,
+                dir: None,
+                domain: None,
+                updated_at: None,
             };
             assert!(invalid_file_empty_name.validate().is_err());
 
             let invalid_file_no_versions = FileItem {
                 name: FileName::from("test_file"),
                 versions: Vec::new(),
+                layout: FileLayout::Nested,
+                dir: None,
+                domain: None,
+                updated_at: None,
             };
             assert!(invalid_file_no_versions.validate().is_err());
             let invalid_file_version = FileItem {
                 name: FileName::from("test_file"),
                 versions: vec![FileVersion::from("1.0.0"), FileVersion::from("invalid")],
+                layout: FileLayout::Nested,
+                dir: None,
+                domain: None,
+                updated_at: None,
             };
             assert!(invalid_file_version.validate().is_err());
         }
 
+        #[test]
+        fn test_file_item_latest_with_prefix() {
+            let mut file_item = FileItem::new(FileName::from("test_file"));
+            file_item.update(FileVersion::from("1.2.5"));
+            file_item.update(FileVersion::from("1.20.0"));
+            file_item.update(FileVersion::from("1.2.10"));
+            file_item.update(FileVersion::from("1.3.0"));
+
+            assert_eq!(
+                file_item.latest_with_prefix("1.2"),
+                Some(FileVersion::from("1.2.10"))
+            );
+            assert_eq!(
+                file_item.latest_with_prefix("1.20"),
+                Some(FileVersion::from("1.20.0"))
+            );
+            assert_eq!(
+                file_item.latest_with_prefix("1"),
+                Some(FileVersion::from("1.20.0"))
+            );
+            assert_eq!(
+                file_item.latest_with_prefix("1.2.5"),
+                Some(FileVersion::from("1.2.5"))
+            );
+            assert_eq!(file_item.latest_with_prefix("2"), None);
+            assert_eq!(file_item.latest_with_prefix("1.2.1"), None);
+        }
+
         #[test]
         fn test_file_item_update() {
             let mut file_item = FileItem::new(FileName::from("test_file"));
@@ -356,6 +1114,47 @@ mod tests {
     mod test_registry {
         use super::*;
 
+        #[test]
+        fn test_registry_problems() {
+            let mut order =
+                FileItem::from_version(FileName::from("order"), FileVersion::from("1.0.0"));
+            order.update(FileVersion::from("1.a.0"));
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![
+                    order,
+                    FileItem::from_version(FileName::from("invoice"), FileVersion::from("0.1.0")),
+                    FileItem::from_version(FileName::from(""), FileVersion::from("0.1.0")),
+                ],
+            );
+
+            let problems: Vec<String> = registry
+                .problems()
+                .iter()
+                .map(|problem| format!("{}: {}", problem.location, problem.error))
+                .collect();
+            assert_eq!(
+                problems,
+                vec![
+                    "order 1.a.0: [core error] validation error: version: File version can only contain digit characters & dots",
+                    "files[2]: [core error] validation error: name: File name cannot be empty",
+                ]
+            );
+
+            let valid = Registry::from_files(
+                Directory::from("businesses"),
+                vec![FileItem::from_version(
+                    FileName::from("invoice"),
+                    FileVersion::from("0.1.0"),
+                )],
+            );
+            assert!(valid.problems().is_empty());
+            assert_eq!(
+                Registry::new(Directory::from("a/b")).problems()[0].location,
+                "directory"
+            );
+        }
+
         #[test]
         fn test_registry_add_file() {
             let mut registry = Registry::new(Directory::from("test_dir"));
@@ -382,6 +1181,116 @@ mod tests {
             assert!(registry.files[0].versions.contains(&new_version));
         }
 
+        #[test]
+        fn test_registry_latest_updated() {
+            let at = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().to_utc();
+            let mut registry = Registry::from_files(
+                Directory::from("test_dir"),
+                vec![
+                    FileItem::new(FileName::from("legacy")),
+                    FileItem::new(FileName::from("order"))
+                        .with_updated_at(at("2025-03-01T10:00:00Z")),
+                    FileItem::new(FileName::from("invoice"))
+                        .with_updated_at(at("2025-01-15T08:30:00Z")),
+                ],
+            );
+
+            let latest = registry.latest_updated().unwrap();
+            assert_eq!(latest.name, FileName::from("order"));
+
+            registry.touch(&FileName::from("invoice"), at("2025-04-01T00:00:00Z"));
+            let latest = registry.latest_updated().unwrap();
+            assert_eq!(latest.name, FileName::from("invoice"));
+
+            // nothing to tell without any recorded time
+            let registry = Registry::from_files(
+                Directory::from("test_dir"),
+                vec![FileItem::new(FileName::from("legacy"))],
+            );
+            assert!(registry.latest_updated().is_none());
+        }
+
+        #[test]
+        fn test_registry_deduplicate() {
+            let order = |versions: &[&str]| FileItem {
+                versions: versions.iter().map(|v| FileVersion::from(*v)).collect(),
+                ..FileItem::new(FileName::from("order"))
+            };
+            let mut billed_order = order(&["1.0.0", "0.1.0"]);
+            billed_order.domain = Some("billing".to_string());
+
+            // crafted as an old registry file could hold it, bypassing `add_file`
+            let mut registry = Registry::new(Directory::from("test_dir"));
+            registry.files = vec![
+                order(&["0.1.0", "0.2.0", "0.1.0"]),
+                FileItem::new(FileName::from("invoice")),
+                billed_order,
+            ];
+
+            let duplicates = registry.deduplicate();
+            assert_eq!(
+                duplicates,
+                vec![
+                    "duplicate order entry",
+                    "duplicate order 0.1.0 version",
+                    "duplicate order 0.1.0 version",
+                ]
+            );
+
+            let mut expected = order(&["0.1.0", "0.2.0", "1.0.0"]);
+            expected.domain = Some("billing".to_string());
+            assert_eq!(
+                registry.files,
+                vec![expected, FileItem::new(FileName::from("invoice"))]
+            );
+
+            // a clean registry is left as is
+            assert!(registry.deduplicate().is_empty());
+        }
+
+        #[test]
+        fn test_registry_from_files() {
+            let mut updated_file_item = FileItem::new(FileName::from("test_file"));
+            updated_file_item.update(FileVersion::from("1.0.0"));
+
+            let registry = Registry::from_files(
+                Directory::from("test_dir"),
+                vec![
+                    FileItem::new(FileName::from("test_file")),
+                    FileItem::new(FileName::from("other_file")),
+                    updated_file_item.clone(),
+                ],
+            );
+
+            assert_eq!(registry.directory(), &Directory::from("test_dir"));
+            assert_eq!(registry.files.len(), 2);
+            assert_eq!(
+                registry.get_file(&FileName::from("test_file")),
+                Some(&updated_file_item)
+            );
+        }
+
+        #[test]
+        fn test_registry_upsert_version() {
+            let name = FileName::from("test_file");
+            let mut registry = Registry::from_files(
+                Directory::from("test_dir"),
+                vec![FileItem::new(name.clone())],
+            );
+
+            assert!(registry.upsert_version(&name, FileVersion::from("1.0.0")));
+            assert!(!registry.upsert_version(&name, FileVersion::from("1.0.0")));
+            assert!(!registry.upsert_version(&name, FileVersion::new()));
+            assert_eq!(
+                registry.get_file(&name).unwrap().versions,
+                vec![FileVersion::new(), FileVersion::from("1.0.0")]
+            );
+
+            let unknown = FileName::from("unknown");
+            assert!(!registry.upsert_version(&unknown, FileVersion::from("1.0.0")));
+            assert!(registry.get_file(&unknown).is_none());
+        }
+
         #[test]
         fn test_registry_remove_file() {
             let mut registry = Registry::new(Directory::from("test_dir"));
@@ -413,6 +1322,158 @@ mod tests {
             assert_eq!(registry.get_file(&non_existing_file_name), None);
         }
 
+        #[test]
+        fn test_registry_gc() {
+            let mut registry = Registry::new(Directory::from("test_dir"));
+            registry.add_file(FileItem::new(FileName::from("kept_file")));
+
+            let mut emptied_file = FileItem::new(FileName::from("emptied_file"));
+            emptied_file.versions.clear();
+            assert!(emptied_file.validate().is_err());
+            registry.add_file(emptied_file);
+            assert_eq!(registry.files.len(), 2);
+
+            registry.gc();
+            assert_eq!(registry.files.len(), 1);
+            assert!(registry.get_file(&FileName::from("kept_file")).is_some());
+            assert!(registry.get_file(&FileName::from("emptied_file")).is_none());
+        }
+
+        #[test]
+        fn test_registry_set_directory() {
+            let mut registry = Registry::new(Directory::from("test_dir"));
+
+            assert!(registry.set_directory(Directory::from("renamed")).is_ok());
+            assert_eq!(registry.directory(), &Directory::from("renamed"));
+
+            let result = registry.set_directory(Directory::from(""));
+            assert!(result.is_err());
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Directory cannot be empty"));
+            assert_eq!(registry.directory(), &Directory::from("renamed"));
+
+            assert!(registry.set_directory(Directory::from("a/b")).is_err());
+        }
+
+        #[test]
+        fn test_registry_summary() {
+            let mut order = FileItem::from_version(FileName::from("order"), "1.2.0".into());
+            order.update(FileVersion::from("1.10.0"));
+            order.update(FileVersion::from("1.9.0"));
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![order, FileItem::new(FileName::from("billing"))],
+            );
+
+            assert_eq!(registry.summary(1000), "order 1.10.0\nbilling 0.1.0");
+        }
+
+        #[test]
+        fn test_registry_summary_truncated() {
+            let files = ["order", "billing", "shipping"]
+                .into_iter()
+                .map(|name| FileItem::new(FileName::from(name)))
+                .collect();
+            let registry = Registry::from_files(Directory::from("businesses"), files);
+
+            assert_eq!(
+                registry.summary(20),
+                "order 0.1.0\n... 2 more files omitted"
+            );
+            assert_eq!(registry.summary(0), "... 3 more files omitted");
+        }
+
+        #[test]
+        fn test_registry_case_collision() {
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![FileItem::new(FileName::from("Order"))],
+            );
+
+            let collision = registry.case_collision(&FileName::from("order"));
+            assert_eq!(collision.unwrap().name, FileName::from("Order"));
+            assert!(registry.case_collision(&FileName::from("Order")).is_none());
+            assert!(registry
+                .case_collision(&FileName::from("billing"))
+                .is_none());
+        }
+
+        #[test]
+        fn test_registry_sort() {
+            let mut order = FileItem::from_version(FileName::from("order"), "1.10.0".into());
+            order.update(FileVersion::from("1.2.0"));
+            let mut billing = FileItem::from_version(FileName::from("billing"), "2.0.0".into());
+            billing.update(FileVersion::from("1.0.0"));
+
+            let mut registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![order.clone(), billing.clone()],
+            );
+            registry.sort();
+
+            let names: Vec<&str> = registry.files.iter().map(|f| f.name.as_str()).collect();
+            assert_eq!(names, vec!["billing", "order"]);
+            assert_eq!(
+                registry.files[1].versions,
+                vec![FileVersion::from("1.2.0"), FileVersion::from("1.10.0")]
+            );
+
+            let sorted_once = registry.clone();
+            registry.sort();
+            assert_eq!(registry, sorted_once);
+
+            let mut reversed =
+                Registry::from_files(Directory::from("businesses"), vec![billing, order]);
+            reversed.sort();
+            assert_eq!(reversed, sorted_once);
+        }
+
+        #[test]
+        fn test_registry_version_histogram() {
+            let mut order = FileItem::from_version(FileName::from("order"), "1.0.0".into());
+            order.update(FileVersion::from("1.1.0"));
+            order.update(FileVersion::from("1.2.0"));
+            let mut billing = FileItem::from_version(FileName::from("billing"), "1.0.0".into());
+            billing.update(FileVersion::from("2.0.0"));
+            let shipping = FileItem::from_version(FileName::from("shipping"), "1.0.0".into());
+            let stock = FileItem::from_version(FileName::from("stock"), "0.1.0".into());
+
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![order, billing, shipping, stock],
+            );
+            assert_eq!(
+                registry.version_histogram(),
+                BTreeMap::from([(1, 2), (2, 1), (3, 1)])
+            );
+            assert!(Registry::new(Directory::from("businesses"))
+                .version_histogram()
+                .is_empty());
+        }
+
+        #[test]
+        fn test_registry_checksum() {
+            let order = FileItem::from_version(FileName::from("order"), "1.0.0".into());
+            let billing = FileItem::from_version(FileName::from("billing"), "2.0.0".into());
+
+            let registry = Registry::from_files(
+                Directory::from("businesses"),
+                vec![order.clone(), billing.clone()],
+            );
+            let checksum = registry.checksum().unwrap();
+            assert_eq!(checksum.len(), 16);
+
+            let reversed =
+                Registry::from_files(Directory::from("businesses"), vec![billing, order]);
+            assert_eq!(reversed.checksum().unwrap(), checksum);
+
+            let mut changed = registry.clone();
+            changed.upsert_version(&FileName::from("order"), "1.1.0".into());
+            assert_ne!(changed.checksum().unwrap(), checksum);
+        }
+
         mod test_registry_json {
             use super::*;
 
@@ -430,6 +1491,18 @@ mod tests {
                 let deserialized_registry: Registry = serde_json::from_str(&json).unwrap();
                 assert_eq!(registry, deserialized_registry);
             }
+
+            #[test]
+            fn test_registry_from_json_without_layout() {
+                let json =
+                    r#"{"directory":"test_dir","files":[{"name":"order","versions":["0.1.0"]}]}"#;
+                let registry: Registry = serde_json::from_str(json).unwrap();
+                assert_eq!(registry.files[0].layout, FileLayout::Nested);
+
+                let flat = FileItem::new(FileName::from("order")).with_layout(FileLayout::Flat);
+                let json = serde_json::to_string(&flat).unwrap();
+                assert!(json.contains(r#""layout":"flat""#));
+            }
         }
     }
 }
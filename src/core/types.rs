@@ -1,41 +1,87 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use serde::Serialize;
-use serde_json;
 use thiserror::Error;
+use tracing::warn;
 
 #[derive(Debug, Error)]
 pub(crate) enum CoreError {
     #[error("[core error] json error: {0}")]
     JSONError(#[from] serde_json::Error),
 
-    #[error("[core error] validation error: {0}")]
-    ValidationError(String),
+    /// An invalid value, the field tells which input it was when known.
+    #[error("[core error] validation error: {}", field_message(.field, .message))]
+    ValidationError {
+        field: Option<String>,
+        message: String,
+    },
 }
 
 impl CoreError {
-    pub fn to_string(&self) -> String {
-        match self {
-            CoreError::JSONError(err) => format!("[core error] json error: {}", err),
-            CoreError::ValidationError(msg) => format!("[core error] validation error: {}", msg),
+    /// A validation error which isn't about a single field.
+    pub(crate) fn validation(message: impl Into<String>) -> Self {
+        CoreError::ValidationError {
+            field: None,
+            message: message.into(),
+        }
+    }
+
+    /// A validation error of the given field, e.g. `language`, which prefixes the message.
+    pub(crate) fn field(field: &str, message: impl Into<String>) -> Self {
+        CoreError::ValidationError {
+            field: Some(field.to_string()),
+            message: message.into(),
         }
     }
 }
 
+fn field_message(field: &Option<String>, message: &str) -> String {
+    match field {
+        Some(field) => format!("{}: {}", field, message),
+        None => message.to_string(),
+    }
+}
+
 pub fn validate<T: Validator>(item: &T) -> Result<(), CoreError> {
     item.validate()
 }
 
+/// `validate_all` runs every given validator in order and stops at the first failure.
+///
+/// It's meant for commands validating several independent inputs at once, so they can
+/// use a single call instead of chaining `validate()?` for each of them.
+pub fn validate_all(items: &[&dyn Validator]) -> Result<(), CoreError> {
+    items.iter().try_for_each(|item| item.validate())
+}
+
 pub(crate) trait Validator {
     fn validate(&self) -> Result<(), CoreError>;
 }
 
+/// `impl_non_empty_validator` implements [`Validator`] for a `String` newtype which only
+/// has to be non-empty, failing with the given field and message otherwise.
+macro_rules! impl_non_empty_validator {
+    ($type:ty, $field:expr, $message:expr) => {
+        impl $crate::core::types::Validator for $type {
+            fn validate(&self) -> Result<(), $crate::core::types::CoreError> {
+                if self.0.is_empty() {
+                    return Err($crate::core::types::CoreError::field($field, $message));
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_non_empty_validator;
+
 pub(crate) trait ToJSON {
     fn to_json(&self) -> Result<String, CoreError>
     where
         Self: Serialize,
     {
-        let out = serde_json::to_string_pretty(self).map_err(|err| CoreError::JSONError(err))?;
+        let out = serde_json::to_string_pretty(self).map_err(CoreError::JSONError)?;
         Ok(out)
     }
 }
@@ -51,4 +97,217 @@ pub(crate) trait PathBufWrapper {
     fn to_path_buf(&self) -> PathBuf;
     fn dir_name(&self) -> Option<String>;
     fn exists(&self) -> bool;
+
+    /// Returns the wrapped path with the given part appended.
+    ///
+    /// It mirrors [`Path::join`], so calling it on a bare `PathBuf` behaves the same.
+    fn join<T: AsRef<Path>>(&self, part: T) -> PathBuf {
+        self.to_path_buf().as_path().join(part)
+    }
+
+    /// Whether the wrapped path exists and is a directory.
+    fn is_dir(&self) -> bool {
+        self.to_path_buf().as_path().is_dir()
+    }
+}
+
+/// A bare `PathBuf` is a wrapper of itself, with the same semantics as the command adapter.
+impl PathBufWrapper for PathBuf {
+    fn to_path_buf(&self) -> PathBuf {
+        self.clone()
+    }
+
+    /// Returns the name of the directory the path points to, once its `.` and `..`
+    /// components are resolved lexically, so `/tmp/project/businesses/..` gives `project`.
+    ///
+    /// When no named component is left, like for a relative `..`, the name falls back to
+    /// the one of the canonical path. Only the root has no name at all.
+    fn dir_name(&self) -> Option<String> {
+        let mut names = vec![];
+        for component in self.components() {
+            match component {
+                Component::Normal(name) => names.push(name),
+                Component::ParentDir => _ = names.pop(),
+                Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            }
+        }
+
+        if let Some(name) = names.last() {
+            return name.to_str().map(String::from);
+        }
+
+        warn!(
+            "{} has no directory name, using its canonical path",
+            self.display()
+        );
+        self.canonicalize()
+            .ok()?
+            .file_name()
+            .and_then(|name| name.to_str().map(String::from))
+    }
+
+    fn exists(&self) -> bool {
+        self.as_path().exists()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(Result<(), &'static str>);
+
+    impl Validator for Fixed {
+        fn validate(&self) -> Result<(), CoreError> {
+            self.0.map_err(CoreError::validation)
+        }
+    }
+
+    mod test_non_empty_validator {
+        use super::*;
+
+        struct Label(String);
+
+        impl_non_empty_validator!(Label, "label", "Label cannot be empty");
+
+        #[test]
+        fn test_non_empty_validator() {
+            assert!(Label("order".to_string()).validate().is_ok());
+
+            match Label(String::new()).validate() {
+                Err(CoreError::ValidationError { message: msg, .. }) => {
+                    assert_eq!(msg, "Label cannot be empty")
+                }
+                _ => panic!("Expected ValidationError"),
+            }
+        }
+    }
+
+    mod test_path_buf_wrapper {
+        use super::*;
+
+        #[test]
+        fn test_path_buf_dir_name() {
+            let path = PathBuf::from("/tmp/project/businesses");
+            assert_eq!(path.dir_name(), Some("businesses".to_string()));
+            assert_eq!(
+                PathBufWrapper::to_path_buf(&path),
+                PathBuf::from("/tmp/project/businesses")
+            );
+
+            assert_eq!(
+                PathBuf::from("businesses/").dir_name(),
+                Some("businesses".to_string())
+            );
+            assert_eq!(PathBuf::from("/").dir_name(), None);
+        }
+
+        #[test]
+        fn test_path_buf_dir_name_resolves_components() {
+            assert_eq!(
+                PathBuf::from("/tmp/project/businesses/.").dir_name(),
+                Some("businesses".to_string())
+            );
+            assert_eq!(
+                PathBuf::from("/tmp/project/businesses/..").dir_name(),
+                Some("project".to_string())
+            );
+            assert_eq!(
+                PathBuf::from("../businesses").dir_name(),
+                Some("businesses".to_string())
+            );
+
+            // root-adjacent paths
+            assert_eq!(
+                PathBuf::from("/businesses").dir_name(),
+                Some("businesses".to_string())
+            );
+            assert_eq!(PathBuf::from("/businesses/..").dir_name(), None);
+            assert_eq!(PathBuf::from("/..").dir_name(), None);
+
+            // nothing left to name lexically, the canonical path is used
+            let current_dir = std::env::current_dir().unwrap();
+            assert_eq!(PathBuf::from(".").dir_name(), current_dir.dir_name());
+        }
+
+        #[test]
+        fn test_path_buf_exists() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().to_path_buf();
+
+            assert!(PathBufWrapper::exists(&path));
+            assert!(PathBufWrapper::is_dir(&path));
+            assert!(!PathBufWrapper::exists(&path.join("missing")));
+        }
+    }
+
+    mod test_core_error {
+        use super::*;
+        use std::error::Error as StdError;
+
+        #[test]
+        fn test_display() {
+            let err = CoreError::validation("empty");
+            assert_eq!(err.to_string(), "[core error] validation error: empty");
+            assert!(err.source().is_none());
+
+            let err = CoreError::field("language", "cannot be empty");
+            assert_eq!(
+                err.to_string(),
+                "[core error] validation error: language: cannot be empty"
+            );
+            match err {
+                CoreError::ValidationError { field, message } => {
+                    assert_eq!(field.as_deref(), Some("language"));
+                    assert_eq!(message, "cannot be empty");
+                }
+                _ => panic!("Expected ValidationError"),
+            }
+        }
+
+        #[test]
+        fn test_json_error_source() {
+            let json_err = serde_json::from_str::<u8>("nope").unwrap_err();
+            let expected = json_err.to_string();
+
+            let err = CoreError::from(json_err);
+            assert_eq!(
+                err.to_string(),
+                format!("[core error] json error: {}", expected)
+            );
+
+            let source = err.source().expect("json error should be the source");
+            assert!(source.downcast_ref::<serde_json::Error>().is_some());
+        }
+    }
+
+    mod test_validate_all {
+        use super::*;
+
+        #[test]
+        fn test_all_pass() {
+            let first = Fixed(Ok(()));
+            let second = Fixed(Ok(()));
+
+            assert!(validate_all(&[&first, &second]).is_ok());
+            assert!(validate_all(&[]).is_ok());
+        }
+
+        #[test]
+        fn test_returns_first_failure() {
+            let first = Fixed(Ok(()));
+            let second = Fixed(Err("second failed"));
+            let third = Fixed(Err("third failed"));
+
+            let result = validate_all(&[&first, &second, &third]);
+            assert!(result.is_err());
+
+            match result {
+                Err(CoreError::ValidationError { message: msg, .. }) => {
+                    assert_eq!(msg, "second failed")
+                }
+                _ => panic!("Expected ValidationError"),
+            }
+        }
+    }
 }
@@ -1,40 +1,122 @@
-use clap::Parser;
-use tracing::{debug, info, instrument};
+use std::process::ExitCode;
+
+use clap::{CommandFactory, FromArgMatches};
+use tracing::{debug, info, instrument, warn};
 
 mod core;
 
 mod cli;
-use cli::Commands;
+use cli::{Cli, Commands};
+
+mod logging;
 
 mod commands;
-use commands::business::Handler as BusinessHandler;
+use commands::business::{Handler as BusinessHandler, EMPTY_EXIT_CODE};
+use commands::config::{ConfigSource, Handler as ConfigHandler};
+use commands::env_file;
+use commands::interrupt;
 use commands::project::{Handler as ProjectHandler, Project};
+use commands::version::Handler as VersionHandler;
+use core::business::types::BusinessError;
 
-#[instrument]
-pub fn exec() {
-    debug!("initiate handlers");
-    let project_handler = ProjectHandler::new();
+pub fn exec() -> ExitCode {
+    let mut matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    // loaded before anything else runs, then parsed again so args backed by env vars,
+    // such as the log format, see the loaded values
+    let mut loaded_env = vec![];
+    if let Some(env_file) = &cli.env_file {
+        match env_file::load(env_file, cli.override_env) {
+            Ok(loaded) => loaded_env = loaded,
+            Err(e) => {
+                eprintln!("Error loading env file: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+
+        if !loaded_env.is_empty() {
+            matches = Cli::command().get_matches();
+            cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+        }
+    }
+    cli.log_format_source = ConfigSource::of(&matches, "log_format");
+
+    // Initialize tracing subscriber for logging
+    logging::init(cli.quiet(), cli.log_format);
+    if !loaded_env.is_empty() {
+        debug!("Loaded {} from the env file", loaded_env.join(", "));
+    }
+
+    run(cli)
+}
 
-    debug!("initiate business handler");
-    let business_handler = BusinessHandler::new().expect("Failed to create business handler");
+#[instrument(skip_all)]
+fn run(cli: Cli) -> ExitCode {
+    #[cfg(feature = "interrupt")]
+    if let Err(err) = interrupt::install() {
+        warn!("Failed to install the interrupt handler: {}", err);
+    }
 
-    debug!("parsing CLI arguments");
-    let cli = cli::Cli::parse();
+    debug!("initiate handlers");
+    let project_handler = ProjectHandler::new();
 
     info!("Parsing CLI commands");
     match cli.commands {
-        Commands::Project(args) => match args.commands {
-            Project::Init { name, desc } => {
-                project_handler.init(name, desc);
+        Commands::Project(args) => {
+            let result = match args.commands {
+                Project::Init {
+                    name,
+                    desc,
+                    author,
+                    force,
+                    template_dir,
+                    ..
+                } => project_handler
+                    .with_template_dir(template_dir)
+                    .init(name, desc, author, force, cli.output),
+                Project::Status => project_handler.status(cli.output),
+                Project::Clean { dry_run } => project_handler.clean(dry_run, cli.output),
+            };
+
+            match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("Error handling project command: {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Commands::Config(args) => {
+            let config_handler = ConfigHandler::new(cli.log_format, cli.log_format_source);
+            match config_handler.handle(args, cli.output) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("Error handling config command: {}", e);
+                    ExitCode::FAILURE
+                }
             }
-        },
+        }
+        Commands::Version(args) => {
+            VersionHandler.show(args, cli.output);
+            ExitCode::SUCCESS
+        }
         Commands::Business(args) => {
             info!("Handling business commands");
-            let result = business_handler.define(args);
-            if let Err(e) = result {
-                eprintln!("Error defining business: {}", e);
+            let result = BusinessHandler::new(&args)
+                .and_then(|business_handler| business_handler.handle(args, cli.output));
+            if interrupt::interrupted() {
+                warn!("Business command interrupted");
+                ExitCode::from(interrupt::INTERRUPT_EXIT_CODE)
+            } else if let Err(BusinessError::NoDefinitions) = result {
+                eprintln!("No business definitions are registered");
+                ExitCode::from(EMPTY_EXIT_CODE)
+            } else if let Err(e) = result {
+                eprintln!("Error handling business command: {}", e);
+                ExitCode::FAILURE
             } else {
-                info!("Business defined successfully");
+                info!("Business command handled successfully");
+                ExitCode::SUCCESS
             }
         }
     }
@@ -0,0 +1,40 @@
+use tracing_subscriber::{self, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::cli::LogFormat;
+
+const DEFAULT_LOG_FILTER: &str = "ddai=info,ddai::core=error";
+const QUIET_LOG_FILTER: &str = "ddai=error";
+
+/// `init` installs the global tracing subscriber, logs are always written to stderr.
+///
+/// The filter comes from `RUST_LOG` when set. In quiet mode only errors are logged,
+/// whatever `RUST_LOG` says, so stdout and stderr stay free of informational noise.
+/// The format selects between the human-readable formatter and one JSON object per line.
+pub(crate) fn init(quiet: bool, format: LogFormat) {
+    let env_filter = if quiet {
+        EnvFilter::new(QUIET_LOG_FILTER)
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER))
+    };
+
+    let pretty_layer = (format == LogFormat::Pretty).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+            .with_level(true)
+    });
+
+    let json_layer = (format == LogFormat::Json).then(|| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+            .with_level(true)
+    });
+
+    tracing_subscriber::registry()
+        .with(pretty_layer)
+        .with(json_layer)
+        .with(env_filter)
+        .init();
+}